@@ -0,0 +1,279 @@
+//! Broad-phase collision detection producing unordered pairs, see [`SweepAndPrune`].
+
+use crate::UnorderedPair;
+use std::collections::hash_map::{self, HashMap, RandomState};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::vec::Vec;
+
+/// A sweep-and-prune broad phase that finds the overlapping intervals along one axis
+///
+/// Every interval is keyed by an ID and spans from `min` to `max`, both inclusive.
+/// [`overlapping_pairs`](SweepAndPrune::overlapping_pairs) reports each overlapping pair of IDs exactly once.
+/// For bodies in more than one dimension, the intervals are usually the bounding boxes projected onto
+/// the axis with the most spread, and the reported pairs are candidates for an exact test.
+///
+/// The intervals are kept sorted by their start between calls and re-sorted with an insertion sort.
+/// When the intervals move only a little between frames, the order is nearly sorted
+/// and an update costs close to linear time.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{SweepAndPrune, UnorderedPair};
+///
+/// let mut broad_phase = SweepAndPrune::new();
+/// broad_phase.insert("ball", 0.0, 2.0);
+/// broad_phase.insert("box", 1.5, 3.0);
+/// broad_phase.insert("wall", 10.0, 11.0);
+/// assert_eq!(broad_phase.overlapping_pairs(), [UnorderedPair("box", "ball")]);
+///
+/// broad_phase.insert("ball", 9.0, 10.0);
+/// assert_eq!(broad_phase.overlapping_pairs(), [UnorderedPair("ball", "wall")]);
+/// ```
+#[derive(Clone)]
+pub struct SweepAndPrune<Id, C = f64, S = RandomState> {
+    slots: HashMap<Id, usize, S>,
+    intervals: Vec<Interval<Id, C>>,
+    order: Vec<usize>,
+    active: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct Interval<Id, C> {
+    id: Id,
+    min: C,
+    max: C,
+}
+
+impl<Id, C> SweepAndPrune<Id, C, RandomState> {
+    /// Creates an empty `SweepAndPrune`.
+    pub fn new() -> SweepAndPrune<Id, C, RandomState> {
+        SweepAndPrune::default()
+    }
+}
+
+impl<Id, C, S> SweepAndPrune<Id, C, S> {
+    /// Creates an empty `SweepAndPrune` which will use the given hash builder to hash IDs.
+    pub fn with_hasher(hash_builder: S) -> SweepAndPrune<Id, C, S> {
+        SweepAndPrune {
+            slots: HashMap::with_hasher(hash_builder),
+            intervals: Vec::new(),
+            order: Vec::new(),
+            active: Vec::new(),
+        }
+    }
+
+    /// Returns the number of intervals.
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    /// Returns `true` if there are no intervals.
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Removes all intervals.
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.intervals.clear();
+        self.order.clear();
+    }
+}
+
+impl<Id, C, S> SweepAndPrune<Id, C, S>
+where
+    Id: Hash + Eq + Clone,
+    C: PartialOrd + Copy,
+    S: BuildHasher,
+{
+    /// Sets the interval of `id` to span from `min` to `max`,
+    /// returning the previous bounds if `id` was already present.
+    pub fn insert(&mut self, id: Id, min: C, max: C) -> Option<(C, C)> {
+        match self.slots.entry(id) {
+            hash_map::Entry::Occupied(entry) => {
+                let interval = &mut self.intervals[*entry.get()];
+                let previous = (interval.min, interval.max);
+                interval.min = min;
+                interval.max = max;
+                Some(previous)
+            }
+            hash_map::Entry::Vacant(entry) => {
+                let slot = self.intervals.len();
+                self.intervals.push(Interval {
+                    id: entry.key().clone(),
+                    min,
+                    max,
+                });
+                self.order.push(slot);
+                entry.insert(slot);
+                None
+            }
+        }
+    }
+
+    /// Returns the bounds of the interval of `id`.
+    pub fn get(&self, id: &Id) -> Option<(C, C)> {
+        let interval = &self.intervals[*self.slots.get(id)?];
+        Some((interval.min, interval.max))
+    }
+
+    /// Removes the interval of `id`, returning its bounds if it was present.
+    pub fn remove(&mut self, id: &Id) -> Option<(C, C)> {
+        let slot = self.slots.remove(id)?;
+        let removed = self.intervals.swap_remove(slot);
+        let moved = self.intervals.len();
+
+        self.order.retain(|&other| other != slot);
+        if slot != moved {
+            *self
+                .slots
+                .get_mut(&self.intervals[slot].id)
+                .expect("slot index out of sync with intervals") = slot;
+            for other in &mut self.order {
+                if *other == moved {
+                    *other = slot;
+                }
+            }
+        }
+
+        Some((removed.min, removed.max))
+    }
+
+    /// Returns every pair of IDs whose intervals overlap.
+    ///
+    /// The pairs are ordered by the start of the interval that starts later,
+    /// and the first component is the ID of the interval that starts earlier.
+    pub fn overlapping_pairs(&mut self) -> Vec<UnorderedPair<Id>> {
+        self.sort();
+
+        let mut pairs = Vec::new();
+        let intervals = &self.intervals;
+        self.active.clear();
+
+        for &slot in &self.order {
+            let interval = &intervals[slot];
+            self.active
+                .retain(|&other| intervals[other].max >= interval.min);
+            pairs.extend(
+                self.active
+                    .iter()
+                    .map(|&other| UnorderedPair(intervals[other].id.clone(), interval.id.clone())),
+            );
+            self.active.push(slot);
+        }
+
+        pairs
+    }
+
+    fn sort(&mut self) {
+        let intervals = &self.intervals;
+        let order = &mut self.order;
+
+        for i in 1..order.len() {
+            let mut j = i;
+            while j > 0 && intervals[order[j]].min < intervals[order[j - 1]].min {
+                order.swap(j, j - 1);
+                j -= 1;
+            }
+        }
+    }
+}
+
+impl<Id, C, S: Default> Default for SweepAndPrune<Id, C, S> {
+    fn default() -> SweepAndPrune<Id, C, S> {
+        SweepAndPrune::with_hasher(S::default())
+    }
+}
+
+impl<Id: fmt::Debug, C: fmt::Debug, S> fmt::Debug for SweepAndPrune<Id, C, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self.intervals
+                    .iter()
+                    .map(|interval| (&interval.id, (&interval.min, &interval.max))),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UnorderedPairSet;
+
+    fn brute_force(intervals: &[(u32, i32, i32)]) -> UnorderedPairSet<u32> {
+        let mut pairs = UnorderedPairSet::new();
+        for (i, &(a, a_min, a_max)) in intervals.iter().enumerate() {
+            for &(b, b_min, b_max) in &intervals[i + 1..] {
+                if a_min <= b_max && b_min <= a_max {
+                    pairs.insert(a, b);
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn matches_brute_force_across_frames() {
+        let mut intervals: Vec<(u32, i32, i32)> = (0..40)
+            .map(|id| (id, (id as i32 * 37) % 50, (id as i32 * 37) % 50 + 6))
+            .collect();
+        let mut broad_phase = SweepAndPrune::new();
+
+        for frame in 0..5 {
+            for (id, min, max) in &mut intervals {
+                let step = if *id % 2 == 0 { frame } else { -frame };
+                *min += step;
+                *max += step;
+                broad_phase.insert(*id, *min, *max);
+            }
+            let pairs = broad_phase.overlapping_pairs();
+            let found: UnorderedPairSet<_> = pairs.iter().copied().collect();
+            assert_eq!(found.len(), pairs.len(), "duplicate pair in frame {frame}");
+            assert_eq!(found, brute_force(&intervals), "frame {frame}");
+        }
+    }
+
+    #[test]
+    fn touching_intervals_overlap() {
+        let mut broad_phase = SweepAndPrune::new();
+        broad_phase.insert(1, 0, 1);
+        broad_phase.insert(2, 1, 2);
+        broad_phase.insert(3, 3, 4);
+        assert_eq!(broad_phase.overlapping_pairs(), [UnorderedPair(1, 2)]);
+    }
+
+    #[test]
+    fn remove_keeps_slots_in_sync() {
+        let mut broad_phase = SweepAndPrune::new();
+        broad_phase.insert('a', 0, 10);
+        broad_phase.insert('b', 20, 30);
+        broad_phase.insert('c', 5, 25);
+        assert_eq!(broad_phase.insert('c', 5, 15), Some((5, 25)));
+
+        assert_eq!(broad_phase.remove(&'a'), Some((0, 10)));
+        assert_eq!(broad_phase.remove(&'a'), None);
+        assert_eq!(broad_phase.get(&'c'), Some((5, 15)));
+        assert!(broad_phase.overlapping_pairs().is_empty());
+
+        broad_phase.insert('c', 25, 35);
+        assert_eq!(broad_phase.overlapping_pairs(), [UnorderedPair('b', 'c')]);
+        assert_eq!(broad_phase.len(), 2);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        let mut broad_phase =
+            SweepAndPrune::with_hasher(BuildHasherDefault::<DefaultHasher>::default());
+        broad_phase.insert(1, 0, 2);
+        broad_phase.insert(2, 2, 3);
+        assert_eq!(broad_phase.overlapping_pairs(), [UnorderedPair(1, 2)]);
+        assert_eq!(broad_phase.remove(&1), Some((0, 2)));
+    }
+}
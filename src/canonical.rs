@@ -0,0 +1,81 @@
+//! Serializes an [`UnorderedPair<T>`] with its components ordered smallest to largest.
+//!
+//! The derived serde impls of [`UnorderedPair<T>`] keep the order of the components,
+//! so pairs that compare equal can serialize differently.
+//! Use this module with `#[serde(with = "unordered_pair::canonical")]` to get the same output
+//! for equal pairs, e.g. for deterministic snapshots.
+//! Deserialization accepts the components in either order.
+//!
+//! [`SortedPair<T>`](crate::SortedPair) always serializes in this canonical order.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use unordered_pair::UnorderedPair;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Link {
+//!     #[serde(with = "unordered_pair::canonical")]
+//!     ends: UnorderedPair<u32>,
+//! }
+//!
+//! let link = Link { ends: UnorderedPair(7, 3) };
+//! let json = serde_json::to_string(&link).unwrap();
+//! assert_eq!(json, r#"{"ends":[3,7]}"#);
+//!
+//! let link: Link = serde_json::from_str(r#"{"ends":[7,3]}"#).unwrap();
+//! assert_eq!(link.ends, UnorderedPair(3, 7));
+//! ```
+
+use crate::UnorderedPair;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes `pair` with its components ordered smallest to largest.
+pub fn serialize<T, S>(pair: &UnorderedPair<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Ord + Serialize,
+    S: Serializer,
+{
+    UnorderedPair::from(pair.ordered_refs()).serialize(serializer)
+}
+
+/// Deserializes a pair, accepting its components in either order.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<UnorderedPair<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    UnorderedPair::deserialize(deserializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::String;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Snapshot {
+        #[serde(with = "crate::canonical")]
+        pair: UnorderedPair<String>,
+    }
+
+    #[test]
+    fn equal_pairs_serialize_equally() {
+        let pair = Snapshot {
+            pair: UnorderedPair("b".into(), "a".into()),
+        };
+        let rev = Snapshot {
+            pair: UnorderedPair("a".into(), "b".into()),
+        };
+        let json = serde_json::to_string(&pair).unwrap();
+        assert_eq!(json, r#"{"pair":["a","b"]}"#);
+        assert_eq!(json, serde_json::to_string(&rev).unwrap());
+    }
+
+    #[test]
+    fn deserialize_either_order() {
+        let pair: Snapshot = serde_json::from_str(r#"{"pair":["b","a"]}"#).unwrap();
+        assert_eq!(pair.pair, UnorderedPair("a".into(), "b".into()));
+    }
+}
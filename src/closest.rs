@@ -0,0 +1,416 @@
+//! Closest-pair queries over a set of points, returning the pairs as indices into the input.
+
+use crate::UnorderedPair;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::vec::Vec;
+
+/// A distance function between two points of type `P`
+///
+/// This is implemented for [`Euclidean`] and [`Manhattan`],
+/// and for every closure `Fn(&P, &P) -> D` where `D: PartialOrd`.
+pub trait Metric<P> {
+    /// The type of the distances.
+    type Distance: PartialOrd;
+
+    /// Returns the distance between `a` and `b`.
+    fn distance(&self, a: &P, b: &P) -> Self::Distance;
+}
+
+/// The straight-line distance between two points
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Euclidean;
+
+/// The sum of the absolute differences of the coordinates of two points
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Manhattan;
+
+impl<const D: usize> Metric<[f64; D]> for Euclidean {
+    type Distance = f64;
+
+    fn distance(&self, a: &[f64; D], b: &[f64; D]) -> f64 {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+impl<const D: usize> Metric<[f64; D]> for Manhattan {
+    type Distance = f64;
+
+    fn distance(&self, a: &[f64; D], b: &[f64; D]) -> f64 {
+        a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum()
+    }
+}
+
+impl<P, D, F> Metric<P> for F
+where
+    F: Fn(&P, &P) -> D,
+    D: PartialOrd,
+{
+    type Distance = D;
+
+    fn distance(&self, a: &P, b: &P) -> D {
+        self(a, b)
+    }
+}
+
+/// A [`Metric`] for which the distance between two points is at least the difference of each of their coordinates
+///
+/// This is what [`closest_pair`] relies on to skip pairs. It is sealed and only implemented for
+/// [`Euclidean`] and [`Manhattan`]; other metrics can be used with [`closest_pair_by`].
+///
+/// ```compile_fail
+/// use unordered_pair::closest::closest_pair;
+///
+/// let scaled = |a: &[f64; 2], b: &[f64; 2]| 0.01 * (a[0] - b[0]).hypot(a[1] - b[1]);
+/// closest_pair(&[[0.0, 0.0], [1.0, 1.0]], scaled);
+/// ```
+pub trait CoordinateBounded: Metric<[f64; 2], Distance = f64> + sealed::Sealed {}
+
+impl CoordinateBounded for Euclidean {}
+impl CoordinateBounded for Manhattan {}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Euclidean {}
+    impl Sealed for super::Manhattan {}
+}
+
+/// Returns the indices of the two closest points and their distance, using divide and conquer
+///
+/// This takes `O(n log n)` time. It only accepts the [`CoordinateBounded`] metrics,
+/// for other metrics use [`closest_pair_by`].
+///
+/// Pairs whose distance is NaN are skipped.
+/// Returns `None` if there are fewer than two points.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::closest::{closest_pair, Euclidean, Manhattan};
+/// use unordered_pair::UnorderedPair;
+///
+/// let points = [[0.0, 0.0], [5.0, 5.0], [1.0, 1.0], [2.1, 0.0]];
+/// let (pair, distance) = closest_pair(&points, Euclidean).unwrap();
+/// assert_eq!(pair, UnorderedPair(0, 2));
+/// assert_eq!(distance, 2f64.sqrt());
+///
+/// let (pair, distance) = closest_pair(&points, Manhattan).unwrap();
+/// assert_eq!(pair, UnorderedPair(0, 2));
+/// assert_eq!(distance, 2.0);
+/// ```
+pub fn closest_pair<M>(points: &[[f64; 2]], metric: M) -> Option<(UnorderedPair<usize>, f64)>
+where
+    M: CoordinateBounded,
+{
+    let mut indices: Vec<usize> = (0..points.len()).collect();
+    indices.sort_by(|&a, &b| points[a][0].total_cmp(&points[b][0]));
+
+    let mut search = Search {
+        points,
+        metric,
+        scratch: Vec::with_capacity(points.len()),
+        best: None,
+        best_distance: f64::INFINITY,
+    };
+    search.run(&mut indices);
+    search.best.map(|pair| (pair, search.best_distance))
+}
+
+struct Search<'a, M> {
+    points: &'a [[f64; 2]],
+    metric: M,
+    scratch: Vec<usize>,
+    best: Option<UnorderedPair<usize>>,
+    best_distance: f64,
+}
+
+impl<M: CoordinateBounded> Search<'_, M> {
+    /// Searches `indices`, which are sorted by x, and leaves them sorted by y.
+    fn run(&mut self, indices: &mut [usize]) {
+        if indices.len() <= 3 {
+            for (i, &a) in indices.iter().enumerate() {
+                for &b in &indices[i + 1..] {
+                    self.consider(a, b);
+                }
+            }
+            indices.sort_by(|&a, &b| self.y(a).total_cmp(&self.y(b)));
+            return;
+        }
+
+        let middle = indices.len() / 2;
+        let middle_x = self.points[indices[middle]][0];
+        let (left, right) = indices.split_at_mut(middle);
+        self.run(left);
+        self.run(right);
+        self.merge_by_y(indices, middle);
+
+        self.scratch.clear();
+        self.scratch.extend(
+            indices
+                .iter()
+                .filter(|&&index| (self.points[index][0] - middle_x).abs() < self.best_distance),
+        );
+        let strip = std::mem::take(&mut self.scratch);
+        for (i, &a) in strip.iter().enumerate() {
+            for &b in &strip[i + 1..] {
+                if self.y(b) - self.y(a) >= self.best_distance {
+                    break;
+                }
+                self.consider(a, b);
+            }
+        }
+        self.scratch = strip;
+    }
+
+    fn merge_by_y(&mut self, indices: &mut [usize], middle: usize) {
+        self.scratch.clear();
+        let (mut i, mut j) = (0, middle);
+        while i < middle && j < indices.len() {
+            if self.y(indices[j]) < self.y(indices[i]) {
+                self.scratch.push(indices[j]);
+                j += 1;
+            } else {
+                self.scratch.push(indices[i]);
+                i += 1;
+            }
+        }
+        self.scratch.extend_from_slice(&indices[i..middle]);
+        self.scratch.extend_from_slice(&indices[j..]);
+        indices.copy_from_slice(&self.scratch);
+    }
+
+    fn consider(&mut self, a: usize, b: usize) {
+        let distance = self.metric.distance(&self.points[a], &self.points[b]);
+        // Infinite distances still count, so there is a closest pair whenever there are two points.
+        if !distance.is_nan() && (self.best.is_none() || distance < self.best_distance) {
+            self.best = Some(UnorderedPair(a.min(b), a.max(b)));
+            self.best_distance = distance;
+        }
+    }
+
+    fn y(&self, index: usize) -> f64 {
+        self.points[index][1]
+    }
+}
+
+/// Returns the indices of the two closest points and their distance, comparing every pair
+///
+/// This works with any [`Metric`], but takes `O(n²)` time.
+/// Pairs whose distance is incomparable with itself, such as NaN, are skipped.
+/// If several pairs are equally close, the first one in lexicographic order of the indices is returned.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::closest::closest_pair_by;
+/// use unordered_pair::UnorderedPair;
+///
+/// let words = ["kitten", "sitting", "mitten"];
+/// let differing = |a: &&str, b: &&str| a.chars().zip(b.chars()).filter(|(a, b)| a != b).count();
+///
+/// assert_eq!(closest_pair_by(&words, differing), Some((UnorderedPair(0, 2), 1)));
+/// ```
+pub fn closest_pair_by<T, M>(points: &[T], metric: M) -> Option<(UnorderedPair<usize>, M::Distance)>
+where
+    M: Metric<T>,
+{
+    k_closest_pairs_by(points, 1, metric).pop()
+}
+
+/// Returns the `k` closest pairs of points and their distances, closest first
+///
+/// This works with any [`Metric`], but computes the distance of every pair of points,
+/// taking `O(n² log k)` time.
+/// Pairs whose distance is incomparable with itself, such as NaN, are skipped.
+/// Pairs that are equally close are ordered lexicographically by their indices,
+/// which are also stored in this order.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::closest::{k_closest_pairs_by, Manhattan};
+/// use unordered_pair::UnorderedPair;
+///
+/// let points = [[0.0, 0.0], [3.0, 0.0], [0.0, 1.0], [0.0, 2.5]];
+/// assert_eq!(
+///     k_closest_pairs_by(&points, 2, Manhattan),
+///     [(UnorderedPair(0, 2), 1.0), (UnorderedPair(2, 3), 1.5)]
+/// );
+/// ```
+pub fn k_closest_pairs_by<T, M>(
+    points: &[T],
+    k: usize,
+    metric: M,
+) -> Vec<(UnorderedPair<usize>, M::Distance)>
+where
+    M: Metric<T>,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+
+    // A max-heap of the closest candidates so far, whose top is the first one to be replaced.
+    let mut closest: BinaryHeap<Candidate<M::Distance>> = BinaryHeap::new();
+    for (i, a) in points.iter().enumerate() {
+        for (j, b) in points.iter().enumerate().skip(i + 1) {
+            let distance = metric.distance(a, b);
+            if distance.partial_cmp(&distance).is_none() {
+                continue;
+            }
+            let candidate = Candidate {
+                distance,
+                pair: (i, j),
+            };
+            if closest.len() < k {
+                closest.push(candidate);
+            } else if let Some(mut farthest) = closest.peek_mut() {
+                if candidate < *farthest {
+                    *farthest = candidate;
+                }
+            }
+        }
+    }
+
+    closest
+        .into_sorted_vec()
+        .into_iter()
+        .map(|Candidate { distance, pair }| (UnorderedPair(pair.0, pair.1), distance))
+        .collect()
+}
+
+/// A pair of indices and their distance, ordered by distance and then lexicographically by the indices.
+struct Candidate<D> {
+    distance: D,
+    pair: (usize, usize),
+}
+
+impl<D: PartialOrd> Ord for Candidate<D> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .partial_cmp(&other.distance)
+            .unwrap_or(Ordering::Equal)
+            .then(self.pair.cmp(&other.pair))
+    }
+}
+
+impl<D: PartialOrd> PartialOrd for Candidate<D> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<D: PartialOrd> PartialEq for Candidate<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<D: PartialOrd> Eq for Candidate<D> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points() -> Vec<[f64; 2]> {
+        (0..300)
+            .map(|i| {
+                let i = f64::from(i);
+                [(i * 7.31) % 97.0, (i * 3.17) % 89.0 + (i * 0.013)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn divide_and_conquer_matches_brute_force() {
+        let points = points();
+        let (pair, distance) = closest_pair(&points, Euclidean).unwrap();
+        let (expected_pair, expected) = closest_pair_by(&points, Euclidean).unwrap();
+        assert_eq!(distance, expected);
+        assert_eq!(
+            Euclidean.distance(&points[pair.0], &points[pair.1]),
+            expected
+        );
+        assert_eq!(pair, expected_pair);
+
+        let (_, distance) = closest_pair(&points, Manhattan).unwrap();
+        assert_eq!(
+            Some(distance),
+            closest_pair_by(&points, Manhattan).map(|(_, d)| d)
+        );
+    }
+
+    #[test]
+    fn too_few_points() {
+        assert_eq!(closest_pair(&[], Euclidean), None);
+        assert_eq!(closest_pair(&[[1.0, 2.0]], Euclidean), None);
+        assert_eq!(closest_pair_by(&[[1.0, 2.0]], Manhattan), None);
+        assert!(k_closest_pairs_by(&points(), 0, Euclidean).is_empty());
+    }
+
+    #[test]
+    fn nan_distances_are_skipped() {
+        let points = [[0.0, 0.0], [f64::NAN, 0.0], [0.0, 3.0]];
+        assert_eq!(
+            closest_pair(&points, Euclidean),
+            Some((UnorderedPair(0, 2), 3.0))
+        );
+        assert_eq!(k_closest_pairs_by(&points, 5, Euclidean).len(), 1);
+    }
+
+    #[test]
+    fn infinite_distances_are_kept() {
+        for points in [
+            &[[0.0, 0.0], [1e308, 0.0], [-1e308, 0.0]][..],
+            &[[0.0, 0.0], [f64::INFINITY, 0.0]],
+        ] {
+            let (pair, distance) = closest_pair(points, Euclidean).unwrap();
+            assert_eq!(distance, f64::INFINITY);
+            assert_eq!(
+                Euclidean.distance(&points[pair.0], &points[pair.1]),
+                distance
+            );
+            assert_eq!(closest_pair_by(points, Euclidean).unwrap().1, distance);
+        }
+    }
+
+    #[test]
+    fn k_closest_breaks_ties_by_index() {
+        let points = [0, 10, 1, 11, 2];
+        let pairs = k_closest_pairs_by(&points, 3, |a: &i32, b: &i32| (a - b).abs());
+        assert_eq!(
+            pairs,
+            [
+                (UnorderedPair(0, 2), 1),
+                (UnorderedPair(1, 3), 1),
+                (UnorderedPair(2, 4), 1)
+            ]
+        );
+        assert_eq!(
+            k_closest_pairs_by(&points, 100, |a: &i32, b: &i32| (a - b).abs()).len(),
+            10
+        );
+    }
+
+    #[test]
+    fn k_closest_with_all_pairs() {
+        let points: Vec<i64> = (0..600).map(|i| (i * 7919) % 1000).collect();
+        let metric = |a: &i64, b: &i64| (a - b).abs();
+        let pairs = k_closest_pairs_by(&points, usize::MAX, metric);
+
+        let mut expected: Vec<_> = (0..points.len())
+            .flat_map(|i| (i + 1..points.len()).map(move |j| (i, j)))
+            .map(|(i, j)| ((points[i] - points[j]).abs(), i, j))
+            .collect();
+        expected.sort();
+        assert_eq!(pairs.len(), expected.len());
+        assert!(pairs
+            .iter()
+            .zip(&expected)
+            .all(|(&(pair, distance), &(d, i, j))| (pair.0, pair.1, distance) == (i, j, d)));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::UnorderedPair;
+use core::hash::{BuildHasher, Hash, Hasher};
+#[cfg(feature = "std")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "std")]
+use std::hash::BuildHasherDefault;
+
+/// Feeds an [`UnorderedPair<T>`] into `state` without requiring `T: Ord`.
+///
+/// Each component is hashed into a separate hasher created by `build_hasher`.
+/// The two resulting hashes are then written to `state` smallest to largest,
+/// which makes the result independent of the order of the components.
+///
+/// Pairs that are equal according to [`PartialEq`] produce the same hash,
+/// as long as the same `build_hasher` is used.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{BuildHasherDefault, Hasher};
+/// use unordered_pair::{hash_commutative, UnorderedPair};
+///
+/// let build_hasher = BuildHasherDefault::<DefaultHasher>::default();
+/// let hash = |pair: &UnorderedPair<&str>| {
+///     let mut hasher = DefaultHasher::new();
+///     hash_commutative(pair, &build_hasher, &mut hasher);
+///     hasher.finish()
+/// };
+///
+/// assert_eq!(hash(&UnorderedPair("a", "b")), hash(&UnorderedPair("b", "a")));
+/// ```
+pub fn hash_commutative<T, S, H>(pair: &UnorderedPair<T>, build_hasher: &S, state: &mut H)
+where
+    T: Hash,
+    S: BuildHasher,
+    H: Hasher,
+{
+    let hash_one = |value: &T| {
+        let mut hasher = build_hasher.build_hasher();
+        value.hash(&mut hasher);
+        hasher.finish()
+    };
+    let first = hash_one(&pair.0);
+    let second = hash_one(&pair.1);
+
+    state.write_u64(first.min(second));
+    state.write_u64(first.max(second));
+}
+
+/// A wrapper around [`UnorderedPair<T>`] that implements [`Hash`] for `T: Hash`
+///
+/// Unlike the [`Hash`] impl of [`UnorderedPair<T>`], this doesn't require `T: Ord`.
+/// See [`hash_commutative`] for how the hash is computed.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashSet;
+/// use unordered_pair::{CommutativeHash, UnorderedPair};
+///
+/// #[derive(PartialEq, Eq, Hash)]
+/// struct EntityId(u64);
+///
+/// let mut set = HashSet::new();
+/// set.insert(CommutativeHash::<_>::new(UnorderedPair(EntityId(1), EntityId(2))));
+///
+/// assert!(set.contains(&CommutativeHash::new(UnorderedPair(EntityId(2), EntityId(1)))));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct CommutativeHash<T, S = BuildHasherDefault<DefaultHasher>> {
+    pair: UnorderedPair<T>,
+    build_hasher: S,
+}
+
+#[cfg(feature = "std")]
+impl<T, S: Default> CommutativeHash<T, S> {
+    /// Wraps `pair`, hashing its components with a default-constructed `S`.
+    pub fn new(pair: UnorderedPair<T>) -> CommutativeHash<T, S> {
+        CommutativeHash::with_hasher(pair, S::default())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S> CommutativeHash<T, S> {
+    /// Wraps `pair`, hashing its components with hashers built by `build_hasher`.
+    ///
+    /// `build_hasher` must produce the same hashes for all wrappers that are compared
+    /// with each other, which rules out randomly seeded builders created per wrapper.
+    pub fn with_hasher(pair: UnorderedPair<T>, build_hasher: S) -> CommutativeHash<T, S> {
+        CommutativeHash { pair, build_hasher }
+    }
+
+    /// Returns a reference to the wrapped pair.
+    pub fn get(&self) -> &UnorderedPair<T> {
+        &self.pair
+    }
+
+    /// Unwraps the pair.
+    pub fn into_inner(self) -> UnorderedPair<T> {
+        self.pair
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, S: Default> From<UnorderedPair<T>> for CommutativeHash<T, S> {
+    fn from(pair: UnorderedPair<T>) -> CommutativeHash<T, S> {
+        CommutativeHash::new(pair)
+    }
+}
+
+/// Compares the wrapped pairs while disregarding the order of the contained items
+#[cfg(feature = "std")]
+impl<T, S> PartialEq for CommutativeHash<T, S>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &CommutativeHash<T, S>) -> bool {
+        self.pair == other.pair
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Eq, S> Eq for CommutativeHash<T, S> {}
+
+/// Computes the same hash regardless of the order of the contained items
+#[cfg(feature = "std")]
+impl<T, S> Hash for CommutativeHash<T, S>
+where
+    T: Hash,
+    S: BuildHasher,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        hash_commutative(&self.pair, &self.build_hasher, state);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn hash_different_internal_order() {
+        let pair: CommutativeHash<_> = UnorderedPair("a", "b").into();
+        let rev: CommutativeHash<_> = UnorderedPair("b", "a").into();
+        assert_eq!(hash_of(&pair), hash_of(&rev));
+    }
+
+    #[test]
+    fn hash_distinguishes_components() {
+        let pair: CommutativeHash<_> = UnorderedPair(1, 2).into();
+        let other: CommutativeHash<_> = UnorderedPair(1, 3).into();
+        assert_ne!(hash_of(&pair), hash_of(&other));
+    }
+
+    #[test]
+    fn hash_set_of_non_ord_components() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Handle(u32);
+
+        let key = |a, b| CommutativeHash::<_>::new(UnorderedPair(Handle(a), Handle(b)));
+        let mut set = HashSet::new();
+
+        assert!(set.insert(key(1, 2)));
+        assert!(!set.insert(key(2, 1)));
+        assert!(set.contains(&key(2, 1)));
+        assert!(!set.contains(&key(1, 1)));
+    }
+}
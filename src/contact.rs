@@ -0,0 +1,290 @@
+//! Frame-to-frame tracking of pairs, see [`ContactTracker`].
+
+use crate::map::PairKey;
+use crate::UnorderedPair;
+use std::collections::hash_map::RandomState;
+use std::collections::{hash_set, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::mem;
+
+/// Tracks which pairs started, ended or persisted between consecutive frames
+///
+/// Each call to [`update`](ContactTracker::update) receives the pairs of the current frame,
+/// for example the overlapping bodies found by a collision detection pass,
+/// and compares them with the pairs of the previous frame.
+/// The order of the components within a pair doesn't matter, and duplicates are ignored.
+///
+/// The tracker keeps two sets that swap roles on every update,
+/// so after a warm-up it doesn't allocate as long as the number of pairs doesn't grow.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{ContactEvent, ContactTracker, UnorderedPair};
+///
+/// let mut contacts = ContactTracker::new();
+/// contacts.update([UnorderedPair(1, 2), UnorderedPair(2, 3)]);
+///
+/// let mut events: Vec<_> = contacts.update([UnorderedPair(3, 2), UnorderedPair(1, 3)]).collect();
+/// events.sort();
+/// assert_eq!(
+///     events,
+///     [
+///         ContactEvent::Started(&UnorderedPair(1, 3)),
+///         ContactEvent::Persisted(&UnorderedPair(2, 3)),
+///         ContactEvent::Ended(&UnorderedPair(1, 2)),
+///     ]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct ContactTracker<T, S = RandomState> {
+    previous: HashSet<UnorderedPair<T>, S>,
+    current: HashSet<UnorderedPair<T>, S>,
+}
+
+impl<T> ContactTracker<T, RandomState> {
+    /// Creates an empty `ContactTracker`.
+    pub fn new() -> ContactTracker<T, RandomState> {
+        ContactTracker::default()
+    }
+}
+
+impl<T, S: Clone> ContactTracker<T, S> {
+    /// Creates an empty `ContactTracker` which will use the given hash builder to hash pairs.
+    pub fn with_hasher(hash_builder: S) -> ContactTracker<T, S> {
+        ContactTracker {
+            previous: HashSet::with_hasher(hash_builder.clone()),
+            current: HashSet::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<T, S> ContactTracker<T, S> {
+    /// An iterator visiting the pairs of the current frame in arbitrary order.
+    pub fn current(&self) -> hash_set::Iter<'_, UnorderedPair<T>> {
+        self.current.iter()
+    }
+
+    /// An iterator visiting the pairs of the previous frame in arbitrary order.
+    pub fn previous(&self) -> hash_set::Iter<'_, UnorderedPair<T>> {
+        self.previous.iter()
+    }
+
+    /// Forgets the pairs of both frames, keeping the allocated memory.
+    ///
+    /// Every pair of the next update is reported as started.
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+}
+
+impl<T, S> ContactTracker<T, S>
+where
+    T: Ord + Hash,
+    S: BuildHasher,
+{
+    /// Advances to the next frame with the given pairs and returns the resulting events.
+    ///
+    /// The events can be queried again later with [`events`](ContactTracker::events)
+    /// or the methods for the individual categories.
+    pub fn update<I>(&mut self, pairs: I) -> Events<'_, T, S>
+    where
+        I: IntoIterator<Item = UnorderedPair<T>>,
+    {
+        mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+        self.current.extend(pairs);
+        self.events()
+    }
+
+    /// Returns `true` if the current frame contains the pair `{a, b}`.
+    pub fn contains(&self, a: &T, b: &T) -> bool {
+        self.current
+            .contains(&UnorderedPair(a, b) as &dyn PairKey<T>)
+    }
+
+    /// An iterator visiting the pairs that are in the current frame but weren't in the previous one.
+    pub fn started(&self) -> hash_set::Difference<'_, UnorderedPair<T>, S> {
+        self.current.difference(&self.previous)
+    }
+
+    /// An iterator visiting the pairs that were in the previous frame but aren't in the current one.
+    pub fn ended(&self) -> hash_set::Difference<'_, UnorderedPair<T>, S> {
+        self.previous.difference(&self.current)
+    }
+
+    /// An iterator visiting the pairs that are in both the previous and the current frame.
+    pub fn persisted(&self) -> hash_set::Intersection<'_, UnorderedPair<T>, S> {
+        self.current.intersection(&self.previous)
+    }
+
+    /// An iterator visiting the started, persisted and ended pairs, in this order.
+    pub fn events(&self) -> Events<'_, T, S> {
+        Events {
+            started: self.started(),
+            persisted: self.persisted(),
+            ended: self.ended(),
+        }
+    }
+}
+
+impl<T, S: Default> Default for ContactTracker<T, S> {
+    fn default() -> ContactTracker<T, S> {
+        ContactTracker {
+            previous: HashSet::default(),
+            current: HashSet::default(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for ContactTracker<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContactTracker")
+            .field("previous", &self.previous)
+            .field("current", &self.current)
+            .finish()
+    }
+}
+
+/// A change in the pairs between two consecutive frames of a [`ContactTracker`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContactEvent<'a, T> {
+    /// The pair is in the current frame but wasn't in the previous one.
+    Started(&'a UnorderedPair<T>),
+    /// The pair is in both the previous and the current frame.
+    Persisted(&'a UnorderedPair<T>),
+    /// The pair was in the previous frame but isn't in the current one.
+    Ended(&'a UnorderedPair<T>),
+}
+
+impl<'a, T> ContactEvent<'a, T> {
+    /// Returns the pair the event is about.
+    pub fn pair(self) -> &'a UnorderedPair<T> {
+        match self {
+            ContactEvent::Started(pair)
+            | ContactEvent::Persisted(pair)
+            | ContactEvent::Ended(pair) => pair,
+        }
+    }
+}
+
+/// An iterator over the [`ContactEvent`]s of the last update of a [`ContactTracker`].
+///
+/// This struct is created by [`ContactTracker::update`] and [`ContactTracker::events`].
+pub struct Events<'a, T, S> {
+    started: hash_set::Difference<'a, UnorderedPair<T>, S>,
+    persisted: hash_set::Intersection<'a, UnorderedPair<T>, S>,
+    ended: hash_set::Difference<'a, UnorderedPair<T>, S>,
+}
+
+impl<T, S> Clone for Events<'_, T, S> {
+    fn clone(&self) -> Self {
+        Events {
+            started: self.started.clone(),
+            persisted: self.persisted.clone(),
+            ended: self.ended.clone(),
+        }
+    }
+}
+
+impl<T, S> fmt::Debug for Events<'_, T, S>
+where
+    T: fmt::Debug + Ord + Hash,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T, S> Iterator for Events<'a, T, S>
+where
+    T: Ord + Hash,
+    S: BuildHasher,
+{
+    type Item = ContactEvent<'a, T>;
+
+    fn next(&mut self) -> Option<ContactEvent<'a, T>> {
+        self.started
+            .next()
+            .map(ContactEvent::Started)
+            .or_else(|| self.persisted.next().map(ContactEvent::Persisted))
+            .or_else(|| self.ended.next().map(ContactEvent::Ended))
+    }
+}
+
+impl<T, S> FusedIterator for Events<'_, T, S>
+where
+    T: Ord + Hash,
+    S: BuildHasher,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn sorted<'a>(pairs: impl Iterator<Item = &'a UnorderedPair<u32>>) -> Vec<(u32, u32)> {
+        let mut pairs: Vec<_> = pairs.map(|pair| pair.into_ordered_tuple()).collect();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn categories_ignore_component_order() {
+        let mut tracker = ContactTracker::new();
+        assert_eq!(
+            tracker
+                .update([UnorderedPair(1, 2), UnorderedPair(4, 3)])
+                .count(),
+            2
+        );
+        tracker.update([
+            UnorderedPair(2, 1),
+            UnorderedPair(5, 6),
+            UnorderedPair(6, 5),
+        ]);
+
+        assert_eq!(sorted(tracker.started()), [(5, 6)]);
+        assert_eq!(sorted(tracker.persisted()), [(1, 2)]);
+        assert_eq!(sorted(tracker.ended()), [(3, 4)]);
+        assert!(tracker.contains(&6, &5));
+    }
+
+    #[test]
+    fn events_are_grouped_by_category() {
+        let mut tracker = ContactTracker::new();
+        tracker.update([UnorderedPair(1, 2), UnorderedPair(2, 3)]);
+        let events: Vec<_> = tracker
+            .update([UnorderedPair(2, 3), UnorderedPair(3, 4)])
+            .collect();
+        assert_eq!(
+            events,
+            [
+                ContactEvent::Started(&UnorderedPair(3, 4)),
+                ContactEvent::Persisted(&UnorderedPair(2, 3)),
+                ContactEvent::Ended(&UnorderedPair(1, 2)),
+            ]
+        );
+        assert_eq!(events[2].pair(), &UnorderedPair(2, 1));
+    }
+
+    #[test]
+    fn empty_update_ends_everything() {
+        let mut tracker = ContactTracker::new();
+        tracker.update([UnorderedPair(1, 2)]);
+        tracker.update([]);
+        assert_eq!(sorted(tracker.ended()), [(1, 2)]);
+        tracker.update([]);
+        assert_eq!(tracker.events().count(), 0);
+
+        tracker.update([UnorderedPair(1, 2)]);
+        tracker.clear();
+        tracker.update([UnorderedPair(1, 2)]);
+        assert_eq!(sorted(tracker.started()), [(1, 2)]);
+    }
+}
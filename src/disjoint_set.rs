@@ -0,0 +1,289 @@
+//! Connected components of a stream of unordered pairs, see [`DisjointSet`].
+
+use crate::UnorderedPair;
+use std::collections::hash_map::{self, HashMap, RandomState};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::vec;
+use std::vec::Vec;
+
+/// A union-find structure that merges the components of the elements of each [`UnorderedPair<T>`] it receives
+///
+/// Elements are added implicitly by [`union`](DisjointSet::union)
+/// or explicitly as singleton components by [`insert`](DisjointSet::insert).
+/// Components are merged by rank, so every lookup takes at most `O(log n)` steps,
+/// and [`union`](DisjointSet::union) additionally compresses the paths it follows.
+/// Queries take `&self` and don't compress paths, so the set is [`Sync`]
+/// and can be shared read-only across threads once it is built.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{DisjointSet, UnorderedPair};
+///
+/// let mut links: DisjointSet<_> = [UnorderedPair("a", "b"), UnorderedPair("c", "d")]
+///     .into_iter()
+///     .collect();
+/// assert!(links.same_component(&"b", &"a"));
+/// assert!(!links.same_component(&"a", &"c"));
+///
+/// links.union(UnorderedPair("d", "a"));
+/// assert!(links.same_component(&"b", &"c"));
+/// assert_eq!(links.components(), [["a", "b", "c", "d"].iter().collect::<Vec<_>>()]);
+/// ```
+#[derive(Clone)]
+pub struct DisjointSet<T, S = RandomState> {
+    indices: HashMap<T, usize, S>,
+    elements: Vec<T>,
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    component_count: usize,
+}
+
+impl<T> DisjointSet<T, RandomState> {
+    /// Creates an empty `DisjointSet`.
+    pub fn new() -> DisjointSet<T, RandomState> {
+        DisjointSet::default()
+    }
+}
+
+impl<T, S> DisjointSet<T, S> {
+    /// Creates an empty `DisjointSet` which will use the given hash builder to hash elements.
+    pub fn with_hasher(hash_builder: S) -> DisjointSet<T, S> {
+        DisjointSet {
+            indices: HashMap::with_hasher(hash_builder),
+            elements: Vec::new(),
+            parents: Vec::new(),
+            ranks: Vec::new(),
+            component_count: 0,
+        }
+    }
+
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Returns the number of components.
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    /// Removes all elements from the set.
+    pub fn clear(&mut self) {
+        self.indices.clear();
+        self.elements.clear();
+        self.parents.clear();
+        self.ranks.clear();
+        self.component_count = 0;
+    }
+
+    /// Returns the elements grouped by component.
+    ///
+    /// The components are ordered by the first insertion of any of their elements,
+    /// and the elements within a component are ordered by insertion.
+    pub fn components(&self) -> Vec<Vec<&T>> {
+        let mut group_of_root = vec![None; self.elements.len()];
+        let mut groups: Vec<Vec<&T>> = Vec::with_capacity(self.component_count);
+
+        for (index, element) in self.elements.iter().enumerate() {
+            let group = *group_of_root[self.root(index)].get_or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(element);
+        }
+
+        groups
+    }
+
+    fn root(&self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            index = self.parents[index];
+        }
+        index
+    }
+
+    fn root_compressing(&mut self, mut index: usize) -> usize {
+        let root = self.root(index);
+        while index != root {
+            index = std::mem::replace(&mut self.parents[index], root);
+        }
+        root
+    }
+}
+
+impl<T, S> DisjointSet<T, S>
+where
+    T: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    /// Adds `element` as a component of its own, returning `false` if it was already present.
+    pub fn insert(&mut self, element: T) -> bool {
+        let len = self.elements.len();
+        self.index_or_insert(element) == len
+    }
+
+    /// Merges the components of both elements of `pair`, adding the elements if they aren't present yet.
+    ///
+    /// Returns `true` if the elements were in different components before.
+    pub fn union(&mut self, pair: UnorderedPair<T>) -> bool {
+        let UnorderedPair(first, second) = pair;
+        let first = self.index_or_insert(first);
+        let second = self.index_or_insert(second);
+        let (first, second) = (self.root_compressing(first), self.root_compressing(second));
+        if first == second {
+            return false;
+        }
+
+        let (parent, child) = if self.ranks[first] < self.ranks[second] {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        self.parents[child] = parent;
+        if self.ranks[parent] == self.ranks[child] {
+            self.ranks[parent] += 1;
+        }
+        self.component_count -= 1;
+        true
+    }
+
+    /// Returns `true` if the set contains `element`.
+    pub fn contains(&self, element: &T) -> bool {
+        self.indices.contains_key(element)
+    }
+
+    /// Returns the element representing the component of `element`.
+    ///
+    /// Two elements are in the same component if and only if they have the same representative.
+    /// The representative may change when components are merged.
+    pub fn representative(&self, element: &T) -> Option<&T> {
+        let index = *self.indices.get(element)?;
+        Some(&self.elements[self.root(index)])
+    }
+
+    /// Returns `true` if both elements are present and in the same component.
+    pub fn same_component(&self, a: &T, b: &T) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(&a), Some(&b)) => self.root(a) == self.root(b),
+            _ => false,
+        }
+    }
+
+    fn index_or_insert(&mut self, element: T) -> usize {
+        match self.indices.entry(element) {
+            hash_map::Entry::Occupied(entry) => *entry.get(),
+            hash_map::Entry::Vacant(entry) => {
+                let index = self.elements.len();
+                self.elements.push(entry.key().clone());
+                self.parents.push(index);
+                self.ranks.push(0);
+                self.component_count += 1;
+                entry.insert(index);
+                index
+            }
+        }
+    }
+}
+
+impl<T, S: Default> Default for DisjointSet<T, S> {
+    fn default() -> DisjointSet<T, S> {
+        DisjointSet::with_hasher(S::default())
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for DisjointSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.components()).finish()
+    }
+}
+
+impl<T, S> Extend<UnorderedPair<T>> for DisjointSet<T, S>
+where
+    T: Hash + Eq + Clone,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = UnorderedPair<T>>>(&mut self, iter: I) {
+        for pair in iter {
+            self.union(pair);
+        }
+    }
+}
+
+impl<T, S> FromIterator<UnorderedPair<T>> for DisjointSet<T, S>
+where
+    T: Hash + Eq + Clone,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = UnorderedPair<T>>>(iter: I) -> DisjointSet<T, S> {
+        let mut set = DisjointSet::default();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_merges_components() {
+        let mut set = DisjointSet::new();
+        assert!(set.union(UnorderedPair(1, 2)));
+        assert!(set.union(UnorderedPair(3, 4)));
+        assert!(!set.union(UnorderedPair(2, 1)));
+        assert_eq!(set.component_count(), 2);
+
+        assert!(set.union(UnorderedPair(4, 1)));
+        assert_eq!(set.component_count(), 1);
+        assert_eq!(set.representative(&3), set.representative(&2));
+        assert_eq!(set.len(), 4);
+    }
+
+    #[test]
+    fn insert_and_loops_create_singletons() {
+        let mut set = DisjointSet::new();
+        assert!(set.insert(1));
+        assert!(!set.insert(1));
+        assert!(!set.union(UnorderedPair(2, 2)));
+        assert_eq!(set.component_count(), 2);
+        assert!(set.same_component(&2, &2));
+        assert!(!set.same_component(&1, &2));
+        assert!(!set.same_component(&3, &3));
+    }
+
+    #[test]
+    fn components_in_insertion_order() {
+        let set: DisjointSet<_> = [
+            UnorderedPair(5, 1),
+            UnorderedPair(2, 3),
+            UnorderedPair(1, 4),
+            UnorderedPair(3, 6),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.components(), [vec![&5, &1, &4], vec![&2, &3, &6]]);
+    }
+
+    #[test]
+    fn long_chain_stays_consistent() {
+        let mut set = DisjointSet::new();
+        for i in 0..1000u32 {
+            set.union(UnorderedPair(i, i + 1));
+        }
+        assert_eq!(set.component_count(), 1);
+        assert!(set.same_component(&0, &1000));
+
+        fn assert_sync<T: Sync>(_: &T) {}
+        assert_sync(&set);
+        set.clear();
+        assert!(set.is_empty());
+        assert!(!set.contains(&0));
+    }
+}
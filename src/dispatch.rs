@@ -0,0 +1,494 @@
+//! Double dispatch on unordered pairs of kinds or types, see [`KindRegistry`] and [`TypeRegistry`].
+
+use crate::map::PairKey;
+use crate::UnorderedPair;
+use std::any::{Any, TypeId};
+use std::boxed::Box;
+use std::collections::hash_map::{HashMap, RandomState};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+type Handlers<K, T, R, M, S> =
+    HashMap<UnorderedPair<K>, (K, Box<<M as Threading>::Handler<T, T, R>>), S>;
+type AnyHandler<M, R> = Box<<M as Threading>::Handler<dyn Any, dyn Any, R>>;
+
+/// Whether the functions of a registry have to be thread-safe, either [`Local`] or [`Shared`]
+///
+/// A registry stores its functions as boxed trait objects,
+/// so it is only `Send` and `Sync` if these bounds are part of the trait object types.
+/// This trait is sealed and can't be implemented outside of this crate.
+pub trait Threading: sealed::Sealed {
+    /// The function that determines the kind of an object.
+    type KindOf<T, K>: ?Sized + Fn(&T) -> K;
+    /// The handler of a pair of objects.
+    type Handler<A: ?Sized, B: ?Sized, R>: ?Sized + Fn(&A, &B) -> R;
+
+    #[doc(hidden)]
+    fn erase<A: Any, B: Any, R: 'static>(
+        handler: Box<Self::Handler<A, B, R>>,
+    ) -> Box<Self::Handler<dyn Any, dyn Any, R>>;
+}
+
+/// The functions of a registry may be any `'static` closures, but the registry is neither `Send` nor `Sync`
+///
+/// This is the default, so handlers can share state through `Rc` and `RefCell`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Local;
+
+/// The functions of a registry have to be `Send + Sync`, and so is the registry
+///
+/// A shared registry can be used by the threads of a parallel step.
+///
+/// # Examples
+///
+/// ```compile_fail
+/// use std::rc::Rc;
+/// use unordered_pair::dispatch::KindRegistry;
+///
+/// let offset = Rc::new(1);
+/// let mut registry = KindRegistry::new_shared(|&n: &u32| n % 2);
+/// registry.register(0, 1, move |even, odd| even + odd + *offset);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Shared;
+
+/// A function that can be stored in a registry with the [`Threading`] `M` as `Target`
+///
+/// This is implemented for all `'static` closures with the signature of `Target`,
+/// which have to be `Send + Sync` for [`Shared`].
+pub trait Storable<M: Threading, Target: ?Sized> {
+    #[doc(hidden)]
+    fn boxed(self) -> Box<Target>;
+}
+
+macro_rules! impl_threading {
+    ($threading:ty, $($bounds:tt)*) => {
+        impl Threading for $threading {
+            type KindOf<T, K> = dyn Fn(&T) -> K $($bounds)*;
+            type Handler<A: ?Sized, B: ?Sized, R> = dyn Fn(&A, &B) -> R $($bounds)*;
+
+            fn erase<A: Any, B: Any, R: 'static>(
+                handler: Box<Self::Handler<A, B, R>>,
+            ) -> Box<Self::Handler<dyn Any, dyn Any, R>> {
+                Box::new(move |a: &dyn Any, b: &dyn Any| {
+                    handler(
+                        a.downcast_ref().expect("handler called with wrong type"),
+                        b.downcast_ref().expect("handler called with wrong type"),
+                    )
+                })
+            }
+        }
+
+        impl<F, T, K> Storable<$threading, dyn Fn(&T) -> K $($bounds)*> for F
+        where
+            F: Fn(&T) -> K $($bounds)* + 'static,
+        {
+            fn boxed(self) -> Box<dyn Fn(&T) -> K $($bounds)*> {
+                Box::new(self)
+            }
+        }
+
+        impl<F, A: ?Sized, B: ?Sized, R> Storable<$threading, dyn Fn(&A, &B) -> R $($bounds)*> for F
+        where
+            F: Fn(&A, &B) -> R $($bounds)* + 'static,
+        {
+            fn boxed(self) -> Box<dyn Fn(&A, &B) -> R $($bounds)*> {
+                Box::new(self)
+            }
+        }
+    };
+}
+
+impl_threading!(Local,);
+impl_threading!(Shared, + Send + Sync);
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Local {}
+    impl Sealed for super::Shared {}
+}
+
+/// A registry of handlers for pairs of objects, keyed by the unordered pair of their kinds
+///
+/// The kind of an object is determined by the function passed to [`new`](KindRegistry::new).
+/// A handler is registered for an ordered pair of kinds, but is found for both orders.
+/// [`dispatch`](KindRegistry::dispatch) swaps the objects if necessary,
+/// so the handler always receives them in the order of the kinds it was registered for.
+///
+/// The [`Threading`] `M` determines whether the functions have to be `Send + Sync`.
+/// By default they don't, use [`new_shared`](KindRegistry::new_shared) for a registry that can be shared between threads.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::dispatch::KindRegistry;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// enum Element {
+///     Sodium,
+///     Chlorine,
+/// }
+///
+/// struct Atom {
+///     element: Element,
+///     charge: i8,
+/// }
+///
+/// let mut reactions = KindRegistry::new(|atom: &Atom| atom.element);
+/// reactions.register(Element::Sodium, Element::Chlorine, |sodium, chlorine| {
+///     format!("NaCl ({:+}/{:+})", sodium.charge, chlorine.charge)
+/// });
+///
+/// let sodium = Atom { element: Element::Sodium, charge: 1 };
+/// let chlorine = Atom { element: Element::Chlorine, charge: -1 };
+/// assert_eq!(reactions.dispatch(&chlorine, &sodium).unwrap(), "NaCl (+1/-1)");
+/// assert_eq!(reactions.dispatch(&sodium, &sodium), None);
+/// ```
+pub struct KindRegistry<K, T, R = (), M: Threading = Local, S = RandomState> {
+    kind_of: Box<M::KindOf<T, K>>,
+    handlers: Handlers<K, T, R, M, S>,
+}
+
+impl<K, T, R> KindRegistry<K, T, R, Local, RandomState> {
+    /// Creates an empty `KindRegistry` that determines the kind of an object with `kind_of`.
+    pub fn new<F>(kind_of: F) -> KindRegistry<K, T, R, Local, RandomState>
+    where
+        F: Fn(&T) -> K + 'static,
+    {
+        KindRegistry::with_hasher(kind_of, RandomState::new())
+    }
+}
+
+impl<K, T, R> KindRegistry<K, T, R, Shared, RandomState> {
+    /// Creates an empty `KindRegistry` whose functions have to be `Send + Sync`,
+    /// and that determines the kind of an object with `kind_of`.
+    pub fn new_shared<F>(kind_of: F) -> KindRegistry<K, T, R, Shared, RandomState>
+    where
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        KindRegistry::with_hasher(kind_of, RandomState::new())
+    }
+}
+
+impl<K, T, R, M: Threading, S> KindRegistry<K, T, R, M, S> {
+    /// Creates an empty `KindRegistry` that determines the kind of an object with `kind_of`
+    /// and will use the given hash builder to hash pairs of kinds.
+    ///
+    /// The [`Threading`] `M` isn't inferred from `kind_of`, it has to follow from the type of the registry.
+    pub fn with_hasher<F>(kind_of: F, hash_builder: S) -> KindRegistry<K, T, R, M, S>
+    where
+        F: Fn(&T) -> K + Storable<M, M::KindOf<T, K>>,
+    {
+        KindRegistry {
+            kind_of: kind_of.boxed(),
+            handlers: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl<K, T, R, M, S> KindRegistry<K, T, R, M, S>
+where
+    K: Ord + Hash + Clone,
+    M: Threading,
+    S: BuildHasher,
+{
+    /// Registers `handler` for objects of the kinds `first` and `second`, in either order.
+    ///
+    /// Returns `true` if this replaced a handler registered for the same kinds.
+    pub fn register<F>(&mut self, first: K, second: K, handler: F) -> bool
+    where
+        F: Fn(&T, &T) -> R + Storable<M, M::Handler<T, T, R>>,
+    {
+        let key = UnorderedPair(first.clone(), second);
+        self.handlers
+            .insert(key, (first, handler.boxed()))
+            .is_some()
+    }
+
+    /// Returns `true` if a handler is registered for the kinds `a` and `b`, in either order.
+    pub fn contains(&self, a: &K, b: &K) -> bool {
+        self.handlers
+            .contains_key(&UnorderedPair(a, b) as &dyn PairKey<K>)
+    }
+
+    /// Removes the handler for the kinds `a` and `b`, returning `true` if there was one.
+    pub fn remove(&mut self, a: &K, b: &K) -> bool {
+        self.handlers
+            .remove(&UnorderedPair(a, b) as &dyn PairKey<K>)
+            .is_some()
+    }
+
+    /// Calls the handler registered for the kinds of `a` and `b`,
+    /// passing the objects in the order the handler was registered for.
+    ///
+    /// Returns `None` if no handler is registered for their kinds.
+    pub fn dispatch(&self, a: &T, b: &T) -> Option<R> {
+        let (kind_a, kind_b) = ((self.kind_of)(a), (self.kind_of)(b));
+        let (first, handler) = self
+            .handlers
+            .get(&UnorderedPair(&kind_a, &kind_b) as &dyn PairKey<K>)?;
+
+        if *first == kind_a {
+            Some(handler(a, b))
+        } else {
+            Some(handler(b, a))
+        }
+    }
+}
+
+impl<K: fmt::Debug, T, R, M: Threading, S> fmt::Debug for KindRegistry<K, T, R, M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+/// A registry of handlers for pairs of objects of different types, keyed by the unordered pair of their [`TypeId`]s
+///
+/// This is the counterpart of [`KindRegistry`] for objects that are only known as [`dyn Any`](Any),
+/// e.g. bodies of a physics engine that are implemented as separate types.
+/// Like there, the handlers only have to be `Send + Sync` for a [`Shared`] registry.
+///
+/// # Examples
+///
+/// ```
+/// use std::any::Any;
+/// use unordered_pair::dispatch::TypeRegistry;
+///
+/// struct Circle(f64);
+/// struct Square(f64);
+///
+/// let mut collisions = TypeRegistry::new();
+/// collisions.register(|circle: &Circle, square: &Square| circle.0 * 2.0 > square.0);
+///
+/// let bodies: [Box<dyn Any>; 2] = [Box::new(Square(1.0)), Box::new(Circle(0.75))];
+/// assert_eq!(collisions.dispatch(&*bodies[0], &*bodies[1]), Some(true));
+/// assert_eq!(collisions.dispatch(&*bodies[0], &*bodies[0]), None);
+/// ```
+pub struct TypeRegistry<R = (), M: Threading = Local, S = RandomState> {
+    handlers: HashMap<UnorderedPair<TypeId>, (TypeId, AnyHandler<M, R>), S>,
+}
+
+impl<R> TypeRegistry<R, Local, RandomState> {
+    /// Creates an empty `TypeRegistry`.
+    pub fn new() -> TypeRegistry<R, Local, RandomState> {
+        TypeRegistry::default()
+    }
+}
+
+impl<R> TypeRegistry<R, Shared, RandomState> {
+    /// Creates an empty `TypeRegistry` whose handlers have to be `Send + Sync`.
+    pub fn new_shared() -> TypeRegistry<R, Shared, RandomState> {
+        TypeRegistry::default()
+    }
+}
+
+impl<R, M: Threading, S> TypeRegistry<R, M, S> {
+    /// Creates an empty `TypeRegistry` which will use the given hash builder to hash pairs of types.
+    pub fn with_hasher(hash_builder: S) -> TypeRegistry<R, M, S> {
+        TypeRegistry {
+            handlers: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl<R, M: Threading, S: BuildHasher> TypeRegistry<R, M, S> {
+    /// Registers `handler` for objects of the types `A` and `B`, in either order.
+    ///
+    /// Returns `true` if this replaced a handler registered for the same types.
+    pub fn register<A, B, F>(&mut self, handler: F) -> bool
+    where
+        A: Any,
+        B: Any,
+        R: 'static,
+        F: Fn(&A, &B) -> R + Storable<M, M::Handler<A, B, R>>,
+    {
+        self.handlers
+            .insert(
+                UnorderedPair(TypeId::of::<A>(), TypeId::of::<B>()),
+                (TypeId::of::<A>(), M::erase(handler.boxed())),
+            )
+            .is_some()
+    }
+
+    /// Returns `true` if a handler is registered for the types `A` and `B`, in either order.
+    pub fn contains<A: Any, B: Any>(&self) -> bool {
+        self.handlers
+            .contains_key(&UnorderedPair(TypeId::of::<A>(), TypeId::of::<B>()))
+    }
+
+    /// Removes the handler for the types `A` and `B`, returning `true` if there was one.
+    pub fn remove<A: Any, B: Any>(&mut self) -> bool {
+        self.handlers
+            .remove(&UnorderedPair(TypeId::of::<A>(), TypeId::of::<B>()))
+            .is_some()
+    }
+
+    /// Calls the handler registered for the types of `a` and `b`,
+    /// passing the objects in the order the handler was registered for.
+    ///
+    /// Returns `None` if no handler is registered for their types.
+    pub fn dispatch(&self, a: &dyn Any, b: &dyn Any) -> Option<R> {
+        let (type_a, type_b) = (Any::type_id(a), Any::type_id(b));
+        let (first, handler) = self.handlers.get(&UnorderedPair(type_a, type_b))?;
+
+        if *first == type_a {
+            Some(handler(a, b))
+        } else {
+            Some(handler(b, a))
+        }
+    }
+}
+
+impl<R, M: Threading, S: Default> Default for TypeRegistry<R, M, S> {
+    fn default() -> TypeRegistry<R, M, S> {
+        TypeRegistry::with_hasher(S::default())
+    }
+}
+
+impl<R, M: Threading, S> fmt::Debug for TypeRegistry<R, M, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::format;
+    use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    enum Kind {
+        Ball,
+        Wall,
+    }
+
+    #[test]
+    fn kind_registry_keeps_registered_orientation() {
+        let mut registry = KindRegistry::new(|&(kind, _): &(Kind, u32)| kind);
+        assert!(!registry.register(Kind::Ball, Kind::Wall, |ball, wall| (ball.1, wall.1)));
+        registry.register(Kind::Ball, Kind::Ball, |a, b| (a.1, b.1));
+
+        let ball = (Kind::Ball, 1);
+        let wall = (Kind::Wall, 2);
+        assert_eq!(registry.dispatch(&ball, &wall), Some((1, 2)));
+        assert_eq!(registry.dispatch(&wall, &ball), Some((1, 2)));
+        assert_eq!(registry.dispatch(&(Kind::Ball, 3), &ball), Some((3, 1)));
+        assert_eq!(registry.dispatch(&wall, &wall), None);
+    }
+
+    #[test]
+    fn kind_registry_replace_and_remove() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = KindRegistry::new(|kind: &Kind| *kind);
+
+        let log = Arc::clone(&calls);
+        registry.register(Kind::Wall, Kind::Ball, move |_, _| {
+            log.lock().unwrap().push("old")
+        });
+        let log = Arc::clone(&calls);
+        assert!(registry.register(Kind::Ball, Kind::Wall, move |_, _| log
+            .lock()
+            .unwrap()
+            .push("new")));
+        assert_eq!(registry.len(), 1);
+
+        registry.dispatch(&Kind::Wall, &Kind::Ball);
+        assert_eq!(*calls.lock().unwrap(), ["new"]);
+
+        assert!(registry.contains(&Kind::Wall, &Kind::Ball));
+        assert!(registry.remove(&Kind::Wall, &Kind::Ball));
+        assert!(!registry.remove(&Kind::Ball, &Kind::Wall));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn local_registries_accept_rc_and_ref_cell() {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let mut kinds = KindRegistry::new(|kind: &Kind| *kind);
+        let log = Rc::clone(&calls);
+        kinds.register(Kind::Ball, Kind::Wall, move |_, _| {
+            log.borrow_mut().push("kinds")
+        });
+
+        let mut types = TypeRegistry::new();
+        let log = Rc::clone(&calls);
+        types.register(move |_: &u8, _: &char| log.borrow_mut().push("types"));
+
+        kinds.dispatch(&Kind::Wall, &Kind::Ball);
+        types.dispatch(&'x', &1u8);
+        assert_eq!(*calls.borrow(), ["kinds", "types"]);
+    }
+
+    #[test]
+    fn shared_registry_with_capturing_kind_of_is_shareable() {
+        let threshold = 10;
+        let mut registry = KindRegistry::new_shared(move |&mass: &u32| mass > threshold);
+        registry.register(true, false, |heavy, light| heavy - light);
+
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+        assert_send_sync(&registry);
+        assert_send_sync(&TypeRegistry::<u32, _>::new_shared());
+
+        let difference =
+            std::thread::scope(|scope| scope.spawn(|| registry.dispatch(&3, &12)).join().unwrap());
+        assert_eq!(difference, Some(9));
+    }
+
+    #[test]
+    fn type_registry_downcasts_in_registered_orientation() {
+        let mut registry = TypeRegistry::new();
+        registry.register(|text: &&str, number: &i32| format!("{text}{number}"));
+        registry.register(|a: &i32, b: &i32| format!("{}", a + b));
+
+        assert_eq!(registry.dispatch(&7, &"x").unwrap(), "x7");
+        assert_eq!(registry.dispatch(&"x", &7).unwrap(), "x7");
+        assert_eq!(registry.dispatch(&1, &2).unwrap(), "3");
+        assert_eq!(registry.dispatch(&"x", &"y"), None);
+
+        assert!(registry.contains::<i32, &str>());
+        assert!(registry.remove::<i32, &str>());
+        assert_eq!(registry.dispatch(&7, &"x"), None);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        let hash_builder = BuildHasherDefault::<DefaultHasher>::default();
+        let mut kinds: KindRegistry<_, _, _, Local, _> =
+            KindRegistry::with_hasher(|kind: &Kind| *kind, hash_builder.clone());
+        kinds.register(Kind::Wall, Kind::Ball, |_, _| 1);
+        assert_eq!(kinds.dispatch(&Kind::Ball, &Kind::Wall), Some(1));
+        assert!(kinds.remove(&Kind::Ball, &Kind::Wall));
+
+        let mut types: TypeRegistry<_, Shared, _> = TypeRegistry::with_hasher(hash_builder);
+        types.register(|a: &u8, b: &u16| u16::from(*a) + b);
+        assert_eq!(types.dispatch(&2u16, &1u8), Some(3));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::UnorderedPair;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+/// An [`UnorderedPair<T>`] whose components are guaranteed to be different
+///
+/// This is useful in domains that forbid self-pairs,
+/// such as collisions between two bodies or edges of a graph without loops.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{DistinctPair, UnorderedPair};
+///
+/// let pair = DistinctPair::new(1, 2).unwrap();
+/// assert!(pair.contains(&1));
+/// assert_eq!(UnorderedPair::from(pair), UnorderedPair(2, 1));
+///
+/// let error = DistinctPair::new(3, 3).unwrap_err();
+/// assert_eq!(error.into_pair(), UnorderedPair(3, 3));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DistinctPair<T>(UnorderedPair<T>);
+
+impl<T: PartialEq> DistinctPair<T> {
+    /// Creates a new `DistinctPair<T>`, or returns an error if both components are equal.
+    pub fn new(first: T, second: T) -> Result<DistinctPair<T>, NotDistinctError<T>> {
+        DistinctPair::try_from(UnorderedPair(first, second))
+    }
+}
+
+impl<T> DistinctPair<T> {
+    /// Returns a reference to the underlying pair.
+    pub fn as_pair(&self) -> &UnorderedPair<T> {
+        &self.0
+    }
+
+    /// Unwraps the underlying pair.
+    pub fn into_pair(self) -> UnorderedPair<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for DistinctPair<T> {
+    type Target = UnorderedPair<T>;
+
+    fn deref(&self) -> &UnorderedPair<T> {
+        &self.0
+    }
+}
+
+impl<T: PartialEq> TryFrom<UnorderedPair<T>> for DistinctPair<T> {
+    type Error = NotDistinctError<T>;
+
+    fn try_from(pair: UnorderedPair<T>) -> Result<DistinctPair<T>, NotDistinctError<T>> {
+        if pair.is_loop() {
+            Err(NotDistinctError { pair })
+        } else {
+            Ok(DistinctPair(pair))
+        }
+    }
+}
+
+impl<T: PartialEq> TryFrom<(T, T)> for DistinctPair<T> {
+    type Error = NotDistinctError<T>;
+
+    fn try_from(tuple: (T, T)) -> Result<DistinctPair<T>, NotDistinctError<T>> {
+        DistinctPair::try_from(UnorderedPair::from(tuple))
+    }
+}
+
+impl<T> From<DistinctPair<T>> for UnorderedPair<T> {
+    fn from(pair: DistinctPair<T>) -> UnorderedPair<T> {
+        pair.0
+    }
+}
+
+impl<T> From<DistinctPair<T>> for (T, T) {
+    fn from(pair: DistinctPair<T>) -> (T, T) {
+        pair.0.into()
+    }
+}
+
+/// Computes the same hash as the underlying [`UnorderedPair<T>`]
+impl<T> Hash for DistinctPair<T>
+where
+    T: Ord + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.0.hash(state);
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for DistinctPair<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+/// Fails if both components are equal
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for DistinctPair<T>
+where
+    T: serde::Deserialize<'de> + PartialEq,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pair = UnorderedPair::deserialize(deserializer)?;
+        DistinctPair::try_from(pair).map_err(serde::de::Error::custom)
+    }
+}
+
+/// The error returned when constructing a [`DistinctPair`] from two equal components.
+///
+/// The rejected pair can be recovered with [`NotDistinctError::into_pair`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct NotDistinctError<T> {
+    pair: UnorderedPair<T>,
+}
+
+impl<T> NotDistinctError<T> {
+    /// Returns the rejected pair.
+    pub fn into_pair(self) -> UnorderedPair<T> {
+        self.pair
+    }
+}
+
+impl<T> fmt::Display for NotDistinctError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("both components of the pair are equal")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for NotDistinctError<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn rejects_equal_components() {
+        assert!(DistinctPair::new(1, 1).is_err());
+        assert!(DistinctPair::try_from((1, 2)).is_ok());
+        assert!(DistinctPair::try_from(UnorderedPair(f32::NAN, f32::NAN)).is_ok());
+    }
+
+    #[test]
+    fn eq_and_hash_match_unordered_pair() {
+        let pair = DistinctPair::new(1, 2).unwrap();
+        let rev = DistinctPair::new(2, 1).unwrap();
+        assert_eq!(pair, rev);
+        assert_eq!(hash_of(&pair), hash_of(&rev));
+        assert_eq!(hash_of(&pair), hash_of(&UnorderedPair(1, 2)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_rejects_equal_components() {
+        let pair: DistinctPair<u8> = serde_json::from_str("[2,1]").unwrap();
+        assert_eq!(pair.into_pair(), UnorderedPair(1, 2));
+        assert!(serde_json::from_str::<DistinctPair<u8>>("[1,1]").is_err());
+        assert_eq!(serde_json::to_string(&pair).unwrap(), "[2,1]");
+    }
+}
@@ -0,0 +1,423 @@
+//! A lightweight undirected graph, see [`UnorderedEdgeGraph`],
+//! and graph algorithms on weighted unordered pairs.
+
+use crate::map::{Neighbors, UnorderedPairMap};
+use crate::{DisjointSet, UnorderedPair};
+use std::cmp::Ordering;
+use std::collections::hash_map::{self, RandomState};
+use std::collections::{hash_set, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::vec::Vec;
+
+/// An undirected graph whose edges are keyed by [`UnorderedPair<N>`] and carry a weight `E`
+///
+/// There is at most one edge between two nodes; [`add_edge`](UnorderedEdgeGraph::add_edge)
+/// rejects duplicates, regardless of the order in which the nodes are given.
+/// Self-loops are allowed and, following the usual convention, count twice towards the degree of their node.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::UnorderedEdgeGraph;
+///
+/// let mut roads = UnorderedEdgeGraph::new();
+/// roads.add_edge("Bern", "Basel", 95).unwrap();
+/// roads.add_edge("Zurich", "Bern", 125).unwrap();
+///
+/// assert_eq!(roads.add_edge("Basel", "Bern", 100), Err(100));
+/// assert_eq!(roads.edge_weight(&"Basel", &"Bern"), Some(&95));
+/// assert_eq!(roads.degree(&"Bern"), 2);
+///
+/// roads.remove_node(&"Bern");
+/// assert_eq!(roads.node_count(), 2);
+/// assert_eq!(roads.edge_count(), 0);
+/// ```
+#[derive(Clone)]
+pub struct UnorderedEdgeGraph<N, E, S = RandomState> {
+    nodes: HashSet<N, S>,
+    edges: UnorderedPairMap<N, E, S>,
+}
+
+impl<N, E> UnorderedEdgeGraph<N, E, RandomState> {
+    /// Creates an empty `UnorderedEdgeGraph`.
+    pub fn new() -> UnorderedEdgeGraph<N, E, RandomState> {
+        UnorderedEdgeGraph::default()
+    }
+}
+
+impl<N, E, S: Clone> UnorderedEdgeGraph<N, E, S> {
+    /// Creates an empty `UnorderedEdgeGraph` which will use the given hash builder to hash nodes.
+    pub fn with_hasher(hash_builder: S) -> UnorderedEdgeGraph<N, E, S> {
+        UnorderedEdgeGraph {
+            nodes: HashSet::with_hasher(hash_builder.clone()),
+            edges: UnorderedPairMap::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<N, E, S> UnorderedEdgeGraph<N, E, S> {
+    /// Returns the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Removes all nodes and edges from the graph.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.edges.clear();
+    }
+
+    /// An iterator visiting all nodes in arbitrary order.
+    pub fn nodes(&self) -> hash_set::Iter<'_, N> {
+        self.nodes.iter()
+    }
+
+    /// An iterator visiting all edges and their weights in arbitrary order.
+    pub fn edges(&self) -> hash_map::Iter<'_, UnorderedPair<N>, E> {
+        self.edges.iter()
+    }
+}
+
+impl<N, E, S> UnorderedEdgeGraph<N, E, S>
+where
+    N: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Adds a node without any edges, returning `false` if it was already present.
+    pub fn add_node(&mut self, node: N) -> bool {
+        self.nodes.insert(node)
+    }
+
+    /// Returns `true` if the graph contains `node`.
+    pub fn contains_node(&self, node: &N) -> bool {
+        self.nodes.contains(node)
+    }
+
+    /// Removes `node` and every edge touching it, returning `false` if it wasn't present.
+    pub fn remove_node(&mut self, node: &N) -> bool {
+        self.edges.remove_all_touching(node);
+        self.nodes.remove(node)
+    }
+
+    /// Adds the edge `{a, b}`, adding the nodes as well if they aren't present yet.
+    ///
+    /// If the graph already contains an edge between `a` and `b`,
+    /// the graph is left unchanged and `weight` is returned as the error.
+    /// Use [`update_edge`](UnorderedEdgeGraph::update_edge) to replace the weight instead.
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> Result<(), E> {
+        if self.edges.contains_pair(&a, &b) {
+            return Err(weight);
+        }
+        self.update_edge(a, b, weight);
+        Ok(())
+    }
+
+    /// Adds the edge `{a, b}` or replaces its weight, returning the previous weight if there was one.
+    pub fn update_edge(&mut self, a: N, b: N, weight: E) -> Option<E> {
+        self.nodes.insert(a.clone());
+        self.nodes.insert(b.clone());
+        self.edges.insert(a, b, weight)
+    }
+
+    /// Returns `true` if the graph contains an edge between `a` and `b`.
+    pub fn contains_edge(&self, a: &N, b: &N) -> bool {
+        self.edges.contains_pair(a, b)
+    }
+
+    /// Returns a reference to the weight of the edge `{a, b}`.
+    pub fn edge_weight(&self, a: &N, b: &N) -> Option<&E> {
+        self.edges.get(a, b)
+    }
+
+    /// Returns a mutable reference to the weight of the edge `{a, b}`.
+    pub fn edge_weight_mut(&mut self, a: &N, b: &N) -> Option<&mut E> {
+        self.edges.get_mut(a, b)
+    }
+
+    /// Removes the edge `{a, b}`, returning its weight if it was present.
+    ///
+    /// The nodes stay in the graph, even if they don't have any edges left.
+    pub fn remove_edge(&mut self, a: &N, b: &N) -> Option<E> {
+        self.edges.remove(a, b)
+    }
+
+    /// An iterator visiting every node adjacent to `node`, in arbitrary order.
+    ///
+    /// A node with a self-loop is visited once as its own neighbor.
+    pub fn neighbors(&self, node: &N) -> Neighbors<'_, N> {
+        self.edges.neighbors(node)
+    }
+
+    /// An iterator visiting every node adjacent to `node` together with the weight of the connecting edge.
+    pub fn adjacent<'a>(&'a self, node: &'a N) -> Adjacent<'a, N, E, S> {
+        Adjacent {
+            node,
+            neighbors: self.edges.neighbors(node),
+            edges: &self.edges,
+        }
+    }
+
+    /// Returns the number of edges touching `node`, counting self-loops twice.
+    pub fn degree(&self, node: &N) -> usize {
+        self.edges.degree(node) + usize::from(self.edges.contains_pair(node, node))
+    }
+}
+
+impl<N, E, S: Default> Default for UnorderedEdgeGraph<N, E, S> {
+    fn default() -> UnorderedEdgeGraph<N, E, S> {
+        UnorderedEdgeGraph {
+            nodes: HashSet::default(),
+            edges: UnorderedPairMap::default(),
+        }
+    }
+}
+
+impl<N: fmt::Debug, E: fmt::Debug, S> fmt::Debug for UnorderedEdgeGraph<N, E, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnorderedEdgeGraph")
+            .field("nodes", &self.nodes)
+            .field("edges", &self.edges)
+            .finish()
+    }
+}
+
+/// An iterator over the nodes adjacent to a given node and the weights of the connecting edges.
+///
+/// This struct is created by [`UnorderedEdgeGraph::adjacent`].
+pub struct Adjacent<'a, N, E, S> {
+    node: &'a N,
+    neighbors: Neighbors<'a, N>,
+    edges: &'a UnorderedPairMap<N, E, S>,
+}
+
+impl<N: fmt::Debug, E, S> fmt::Debug for Adjacent<'_, N, E, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Adjacent")
+            .field("node", self.node)
+            .field("neighbors", &self.neighbors)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<N: Clone, E, S> Clone for Adjacent<'_, N, E, S> {
+    fn clone(&self) -> Self {
+        Adjacent {
+            node: self.node,
+            neighbors: self.neighbors.clone(),
+            edges: self.edges,
+        }
+    }
+}
+
+impl<'a, N, E, S> Iterator for Adjacent<'a, N, E, S>
+where
+    N: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    type Item = (&'a N, &'a E);
+
+    fn next(&mut self) -> Option<(&'a N, &'a E)> {
+        let neighbor = self.neighbors.next()?;
+        let weight = self
+            .edges
+            .get(self.node, neighbor)
+            .expect("neighbor index out of sync with edges");
+        Some((neighbor, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.neighbors.size_hint()
+    }
+}
+
+impl<N, E, S> ExactSizeIterator for Adjacent<'_, N, E, S>
+where
+    N: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+}
+
+impl<N, E, S> FusedIterator for Adjacent<'_, N, E, S>
+where
+    N: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+}
+
+/// Returns the edges of a minimum spanning forest of the given weighted edges, using Kruskal's algorithm
+///
+/// The result contains one minimum spanning tree for each connected component,
+/// with the edges in the order they were selected, i.e. by ascending weight.
+/// Edges of equal weight are considered in the order they were given,
+/// so the result is deterministic even if there are several minimum spanning forests.
+///
+/// For weights that are only [`PartialOrd`], such as floats, use [`minimum_spanning_forest_by`].
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::graph::minimum_spanning_forest;
+/// use unordered_pair::UnorderedPair;
+///
+/// let edges = [
+///     (UnorderedPair('a', 'b'), 4),
+///     (UnorderedPair('b', 'c'), 1),
+///     (UnorderedPair('a', 'c'), 2),
+///     (UnorderedPair('x', 'y'), 7),
+/// ];
+///
+/// assert_eq!(
+///     minimum_spanning_forest(edges),
+///     [UnorderedPair('b', 'c'), UnorderedPair('a', 'c'), UnorderedPair('x', 'y')]
+/// );
+/// ```
+pub fn minimum_spanning_forest<N, W, I>(edges: I) -> Vec<UnorderedPair<N>>
+where
+    I: IntoIterator<Item = (UnorderedPair<N>, W)>,
+    N: Hash + Eq + Clone,
+    W: Ord,
+{
+    minimum_spanning_forest_by(edges, W::cmp)
+}
+
+/// Returns the edges of a minimum spanning forest of the given weighted edges,
+/// comparing the weights with `compare`
+///
+/// This behaves like [`minimum_spanning_forest`], with ties broken by the order the edges were given.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::graph::minimum_spanning_forest_by;
+/// use unordered_pair::UnorderedPair;
+///
+/// let edges = [
+///     (UnorderedPair(0, 1), 0.5),
+///     (UnorderedPair(1, 2), 0.5),
+///     (UnorderedPair(2, 0), 0.25),
+/// ];
+///
+/// assert_eq!(
+///     minimum_spanning_forest_by(edges, f64::total_cmp),
+///     [UnorderedPair(2, 0), UnorderedPair(0, 1)]
+/// );
+/// ```
+pub fn minimum_spanning_forest_by<N, W, I, F>(edges: I, mut compare: F) -> Vec<UnorderedPair<N>>
+where
+    I: IntoIterator<Item = (UnorderedPair<N>, W)>,
+    N: Hash + Eq + Clone,
+    F: FnMut(&W, &W) -> Ordering,
+{
+    let mut edges: Vec<_> = edges.into_iter().collect();
+    edges.sort_by(|(_, a), (_, b)| compare(a, b));
+
+    let mut components = DisjointSet::new();
+    edges
+        .into_iter()
+        .filter_map(|(pair, _)| components.union(pair.clone()).then_some(pair))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn duplicate_edges_are_rejected_in_either_order() {
+        let mut graph = UnorderedEdgeGraph::new();
+        assert_eq!(graph.add_edge(1, 2, 'a'), Ok(()));
+        assert_eq!(graph.add_edge(2, 1, 'b'), Err('b'));
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.update_edge(2, 1, 'c'), Some('a'));
+        assert_eq!(graph.edge_weight(&1, &2), Some(&'c'));
+    }
+
+    #[test]
+    fn degree_counts_loops_twice() {
+        let mut graph = UnorderedEdgeGraph::new();
+        graph.add_edge(1, 1, ()).unwrap();
+        graph.add_edge(1, 2, ()).unwrap();
+        assert_eq!(graph.degree(&1), 3);
+        assert_eq!(graph.degree(&2), 1);
+        assert_eq!(graph.neighbors(&1).count(), 2);
+        assert_eq!(graph.degree(&3), 0);
+    }
+
+    #[test]
+    fn adjacent_yields_weights() {
+        let mut graph = UnorderedEdgeGraph::new();
+        graph.add_edge(1, 2, 10).unwrap();
+        graph.add_edge(3, 1, 30).unwrap();
+        let mut adjacent: Vec<_> = graph.adjacent(&1).collect();
+        adjacent.sort();
+        assert_eq!(adjacent, [(&2, &10), (&3, &30)]);
+        assert_eq!(graph.adjacent(&4).len(), 0);
+    }
+
+    #[test]
+    fn removing_edges_keeps_nodes() {
+        let mut graph = UnorderedEdgeGraph::new();
+        graph.add_node(0);
+        graph.add_edge(1, 2, ()).unwrap();
+        graph.add_edge(2, 3, ()).unwrap();
+        assert_eq!(graph.node_count(), 4);
+
+        assert_eq!(graph.remove_edge(&2, &1), Some(()));
+        assert_eq!(graph.remove_edge(&2, &1), None);
+        assert!(graph.contains_node(&1));
+        assert_eq!(graph.degree(&2), 1);
+
+        assert!(graph.remove_node(&3));
+        assert!(!graph.remove_node(&3));
+        assert_eq!(graph.degree(&2), 0);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn spanning_forest_breaks_ties_by_input_order() {
+        let edges = [
+            (UnorderedPair(1, 2), 1),
+            (UnorderedPair(2, 3), 1),
+            (UnorderedPair(3, 1), 1),
+            (UnorderedPair(3, 3), 0),
+        ];
+        assert_eq!(
+            minimum_spanning_forest(edges),
+            [UnorderedPair(1, 2), UnorderedPair(2, 3)]
+        );
+
+        let rotated = [edges[2], edges[0], edges[1]];
+        assert_eq!(
+            minimum_spanning_forest(rotated),
+            [UnorderedPair(3, 1), UnorderedPair(1, 2)]
+        );
+    }
+
+    #[test]
+    fn spanning_forest_covers_every_component() {
+        let edges = [
+            (UnorderedPair(0, 1), 3.0),
+            (UnorderedPair(2, 3), 1.0),
+            (UnorderedPair(1, 0), 2.0),
+            (UnorderedPair(4, 5), f64::NAN),
+        ];
+        let forest = minimum_spanning_forest_by(edges, f64::total_cmp);
+        assert_eq!(
+            forest,
+            [
+                UnorderedPair(2, 3),
+                UnorderedPair(1, 0),
+                UnorderedPair(4, 5)
+            ]
+        );
+        assert!(minimum_spanning_forest(Vec::<(UnorderedPair<u8>, u8)>::new()).is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+//! A symmetric relation between collision layers, see [`InteractionMatrix`].
+
+use crate::UnorderedPair;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+
+/// A small domain of values that can be used as the layers of an [`InteractionMatrix`]
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::interaction::Layer;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Physics {
+///     Static,
+///     Dynamic,
+///     Trigger,
+/// }
+///
+/// impl Layer for Physics {
+///     const COUNT: usize = 3;
+///
+///     fn index(self) -> usize {
+///         self as usize
+///     }
+///
+///     fn from_index(index: usize) -> Physics {
+///         [Physics::Static, Physics::Dynamic, Physics::Trigger][index]
+///     }
+/// }
+/// ```
+pub trait Layer: Copy {
+    /// The number of layers.
+    const COUNT: usize;
+
+    /// Returns the index of the layer, which must be less than [`COUNT`](Layer::COUNT).
+    fn index(self) -> usize;
+
+    /// Returns the layer with the given index. The inverse of [`index`](Layer::index).
+    fn from_index(index: usize) -> Self;
+}
+
+impl Layer for bool {
+    const COUNT: usize = 2;
+
+    fn index(self) -> usize {
+        usize::from(self)
+    }
+
+    fn from_index(index: usize) -> bool {
+        index != 0
+    }
+}
+
+impl Layer for u8 {
+    const COUNT: usize = 256;
+
+    fn index(self) -> usize {
+        usize::from(self)
+    }
+
+    fn from_index(index: usize) -> u8 {
+        index as u8
+    }
+}
+
+/// A symmetric relation that says which pairs of layers interact, stored as a triangular bitset
+///
+/// Every pair of layers, including a layer with itself, is one bit,
+/// addressed by the [rank](UnorderedPair::rank_with_diagonal) of the pair of layer indices.
+/// Since the relation is symmetric, querying `{a, b}` and `{b, a}` is the same.
+///
+/// With the `serde` feature, the matrix is represented as the list of enabled pairs,
+/// e.g. `[["Player","Enemy"],["Enemy","Enemy"]]` in JSON, so it can be written by hand in config files.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::interaction::{InteractionMatrix, Layer};
+/// use unordered_pair::UnorderedPair;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Physics {
+///     Player,
+///     Enemy,
+///     Pickup,
+/// }
+///
+/// impl Layer for Physics {
+///     const COUNT: usize = 3;
+///
+///     fn index(self) -> usize {
+///         self as usize
+///     }
+///
+///     fn from_index(index: usize) -> Physics {
+///         [Physics::Player, Physics::Enemy, Physics::Pickup][index]
+///     }
+/// }
+///
+/// let mut interactions = InteractionMatrix::new();
+/// interactions.enable_layer(Physics::Player);
+/// interactions.disable(UnorderedPair(Physics::Player, Physics::Player));
+///
+/// assert!(interactions.get(UnorderedPair(Physics::Pickup, Physics::Player)));
+/// assert!(!interactions.get(UnorderedPair(Physics::Enemy, Physics::Pickup)));
+/// assert_eq!(interactions.len(), 2);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct InteractionMatrix<L> {
+    bits: Vec<u64>,
+    layer: PhantomData<L>,
+}
+
+impl<L: Layer> InteractionMatrix<L> {
+    const PAIR_COUNT: usize = match UnorderedPair::<usize>::pair_count_with_diagonal(L::COUNT) {
+        Some(count) => count,
+        None => panic!("capacity overflow"),
+    };
+
+    /// Creates an `InteractionMatrix` in which no layers interact.
+    pub fn new() -> InteractionMatrix<L> {
+        InteractionMatrix {
+            bits: vec![0; Self::PAIR_COUNT / 64 + usize::from(Self::PAIR_COUNT % 64 != 0)],
+            layer: PhantomData,
+        }
+    }
+
+    /// Creates an `InteractionMatrix` in which every layer interacts with every layer, including itself.
+    pub fn all() -> InteractionMatrix<L> {
+        let mut matrix = InteractionMatrix::new();
+        matrix.enable_all();
+        matrix
+    }
+
+    /// Returns `true` if the layers of `pair` interact.
+    pub fn get(&self, pair: UnorderedPair<L>) -> bool {
+        let (word, mask) = Self::bit(pair);
+        self.bits[word] & mask != 0
+    }
+
+    /// Sets whether the layers of `pair` interact, returning whether they did before.
+    pub fn set(&mut self, pair: UnorderedPair<L>, enabled: bool) -> bool {
+        let (word, mask) = Self::bit(pair);
+        let previous = self.bits[word] & mask != 0;
+        if enabled {
+            self.bits[word] |= mask;
+        } else {
+            self.bits[word] &= !mask;
+        }
+        previous
+    }
+
+    /// Lets the layers of `pair` interact.
+    pub fn enable(&mut self, pair: UnorderedPair<L>) {
+        self.set(pair, true);
+    }
+
+    /// Stops the layers of `pair` from interacting.
+    pub fn disable(&mut self, pair: UnorderedPair<L>) {
+        self.set(pair, false);
+    }
+
+    /// Lets `layer` interact with every layer, including itself.
+    pub fn enable_layer(&mut self, layer: L) {
+        self.set_layer(layer, true);
+    }
+
+    /// Stops `layer` from interacting with any layer, including itself.
+    pub fn disable_layer(&mut self, layer: L) {
+        self.set_layer(layer, false);
+    }
+
+    fn set_layer(&mut self, layer: L, enabled: bool) {
+        for index in 0..L::COUNT {
+            self.set(UnorderedPair(layer, L::from_index(index)), enabled);
+        }
+    }
+
+    /// Lets every layer interact with every layer, including itself.
+    pub fn enable_all(&mut self) {
+        self.bits.fill(u64::MAX);
+        if let Some(last) = self.bits.last_mut() {
+            let used = Self::PAIR_COUNT % 64;
+            if used != 0 {
+                *last = (1 << used) - 1;
+            }
+        }
+    }
+
+    /// Stops all layers from interacting.
+    pub fn disable_all(&mut self) {
+        self.bits.fill(0);
+    }
+
+    /// Returns the number of interacting pairs of layers.
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if no layers interact.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&word| word == 0)
+    }
+
+    /// An iterator visiting every interacting pair of layers.
+    ///
+    /// The pairs are visited in the order of their rank, with the layer with the smaller index first.
+    pub fn iter(&self) -> Iter<'_, L> {
+        Iter {
+            bits: &self.bits,
+            word: 0,
+            remaining: self.bits.first().copied().unwrap_or(0),
+            layer: PhantomData,
+        }
+    }
+
+    fn bit(pair: UnorderedPair<L>) -> (usize, u64) {
+        let pair = pair.map(L::index);
+        assert!(
+            pair.0 < L::COUNT && pair.1 < L::COUNT,
+            "layer index out of bounds"
+        );
+        let rank = pair
+            .rank_with_diagonal()
+            .expect("rank is less than the pair count");
+        (rank / 64, 1 << (rank % 64))
+    }
+}
+
+impl<L: Layer> Default for InteractionMatrix<L> {
+    fn default() -> InteractionMatrix<L> {
+        InteractionMatrix::new()
+    }
+}
+
+impl<L: Layer + fmt::Debug> fmt::Debug for InteractionMatrix<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Enables every given pair
+impl<L: Layer> Extend<UnorderedPair<L>> for InteractionMatrix<L> {
+    fn extend<I: IntoIterator<Item = UnorderedPair<L>>>(&mut self, iter: I) {
+        for pair in iter {
+            self.enable(pair);
+        }
+    }
+}
+
+/// Creates a matrix in which exactly the given pairs interact
+impl<L: Layer> FromIterator<UnorderedPair<L>> for InteractionMatrix<L> {
+    fn from_iter<I: IntoIterator<Item = UnorderedPair<L>>>(iter: I) -> InteractionMatrix<L> {
+        let mut matrix = InteractionMatrix::new();
+        matrix.extend(iter);
+        matrix
+    }
+}
+
+impl<'a, L: Layer> IntoIterator for &'a InteractionMatrix<L> {
+    type Item = UnorderedPair<L>;
+    type IntoIter = Iter<'a, L>;
+
+    fn into_iter(self) -> Iter<'a, L> {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<L: Layer + serde::Serialize> serde::Serialize for InteractionMatrix<L> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, L: Layer + serde::Deserialize<'de>> serde::Deserialize<'de> for InteractionMatrix<L> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pairs = Vec::<UnorderedPair<L>>::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+/// An iterator over the interacting pairs of layers of an [`InteractionMatrix`].
+///
+/// This struct is created by [`InteractionMatrix::iter`].
+pub struct Iter<'a, L> {
+    bits: &'a [u64],
+    word: usize,
+    remaining: u64,
+    layer: PhantomData<L>,
+}
+
+impl<L> Clone for Iter<'_, L> {
+    fn clone(&self) -> Self {
+        Iter {
+            bits: self.bits,
+            word: self.word,
+            remaining: self.remaining,
+            layer: PhantomData,
+        }
+    }
+}
+
+impl<L: Layer + fmt::Debug> fmt::Debug for Iter<'_, L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<L: Layer> Iterator for Iter<'_, L> {
+    type Item = UnorderedPair<L>;
+
+    fn next(&mut self) -> Option<UnorderedPair<L>> {
+        while self.remaining == 0 {
+            self.word += 1;
+            self.remaining = *self.bits.get(self.word)?;
+        }
+        let bit = self.remaining.trailing_zeros() as usize;
+        self.remaining &= self.remaining - 1;
+
+        let rank = self.word * 64 + bit;
+        Some(UnorderedPair::<usize>::unrank_with_diagonal(rank).map(L::from_index))
+    }
+}
+
+impl<L: Layer> FusedIterator for Iter<'_, L> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn symmetric_get_and_set() {
+        let mut matrix = InteractionMatrix::<u8>::new();
+        assert!(!matrix.set(UnorderedPair(200, 3), true));
+        assert!(matrix.get(UnorderedPair(3, 200)));
+        assert!(matrix.set(UnorderedPair(3, 200), false));
+        assert!(matrix.is_empty());
+    }
+
+    #[test]
+    fn bulk_updates() {
+        let mut matrix = InteractionMatrix::<u8>::all();
+        assert_eq!(matrix.len(), 256 * 257 / 2);
+        matrix.disable_layer(7);
+        assert_eq!(matrix.len(), 255 * 256 / 2);
+        assert!(!matrix.get(UnorderedPair(7, 255)));
+
+        matrix.disable_all();
+        matrix.enable_layer(0);
+        assert_eq!(matrix.len(), 256);
+        assert_eq!(matrix.iter().count(), 256);
+    }
+
+    #[test]
+    fn iter_in_rank_order() {
+        let matrix: InteractionMatrix<bool> =
+            [UnorderedPair(true, true), UnorderedPair(true, false)]
+                .into_iter()
+                .collect();
+        let pairs: Vec<_> = matrix.iter().collect();
+        assert_eq!(
+            pairs,
+            [UnorderedPair(false, true), UnorderedPair(true, true)]
+        );
+        assert!(!pairs[0].0);
+        assert_eq!(InteractionMatrix::<bool>::all().iter().count(), 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let matrix: InteractionMatrix<u8> = [UnorderedPair(9, 2), UnorderedPair(4, 4)]
+            .into_iter()
+            .collect();
+        let json = serde_json::to_string(&matrix).unwrap();
+        assert_eq!(json, "[[4,4],[2,9]]");
+        assert_eq!(
+            serde_json::from_str::<InteractionMatrix<u8>>(&json).unwrap(),
+            matrix
+        );
+    }
+}
@@ -0,0 +1,513 @@
+//! Iterators over all unordered pairs of a collection, see [`IntoUnorderedPairs`].
+
+use crate::UnorderedPair;
+use core::iter::{FusedIterator, Take};
+use core::slice;
+
+/// An extension trait for iterating over every 2-combination of a slice or a cloneable iterator
+///
+/// Each unordered pair of items is yielded exactly once, so `{a, b}` and `{b, a}` aren't both visited.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{IntoUnorderedPairs, UnorderedPair};
+///
+/// let bodies = ["sun", "earth", "moon"];
+/// let pairs: Vec<_> = bodies.unordered_pairs().collect();
+///
+/// assert_eq!(pairs, [
+///     UnorderedPair(&"sun", &"earth"),
+///     UnorderedPair(&"sun", &"moon"),
+///     UnorderedPair(&"earth", &"moon"),
+/// ]);
+/// assert_eq!(bodies.unordered_pairs_with_diagonal().len(), 6);
+/// ```
+pub trait IntoUnorderedPairs {
+    /// The iterator over the pairs.
+    type Pairs<'a>: Iterator
+    where
+        Self: 'a;
+
+    /// Returns an iterator over every pair of distinct positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics for a slice if the number of pairs overflows `usize`.
+    fn unordered_pairs(&self) -> Self::Pairs<'_>;
+
+    /// Returns an iterator over every pair of positions, including self-pairs such as `{a, a}`.
+    ///
+    /// # Panics
+    ///
+    /// Panics for a slice if the number of pairs overflows `usize`.
+    fn unordered_pairs_with_diagonal(&self) -> Self::Pairs<'_>;
+}
+
+/// Pairs are yielded by their position in the slice in colexicographic order,
+/// i.e. `{s[0], s[1]}, {s[0], s[2]}, {s[1], s[2]}, {s[0], s[3]}, ...`
+/// (see [`UnorderedPair::rank`]).
+impl<T> IntoUnorderedPairs for [T] {
+    type Pairs<'a>
+        = SlicePairs<'a, T>
+    where
+        T: 'a;
+
+    fn unordered_pairs(&self) -> SlicePairs<'_, T> {
+        SlicePairs::new(self, false)
+    }
+
+    fn unordered_pairs_with_diagonal(&self) -> SlicePairs<'_, T> {
+        SlicePairs::new(self, true)
+    }
+}
+
+/// Pairs are yielded in the same colexicographic order as for slices,
+/// i.e. `{x0, x1}, {x0, x2}, {x1, x2}, {x0, x3}, ...`.
+/// The iterator is cloned once per item.
+impl<I> IntoUnorderedPairs for I
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    type Pairs<'a>
+        = IterPairs<I>
+    where
+        I: 'a;
+
+    fn unordered_pairs(&self) -> IterPairs<I> {
+        IterPairs::new(self.clone(), false)
+    }
+
+    fn unordered_pairs_with_diagonal(&self) -> IterPairs<I> {
+        IterPairs::new(self.clone(), true)
+    }
+}
+
+/// An iterator over all unordered pairs of a slice.
+///
+/// Pairs are visited by their [rank](UnorderedPair::rank), so skipping ahead with `nth`
+/// and iterating from the back don't need to walk over the skipped pairs.
+///
+/// This struct is created by [`IntoUnorderedPairs::unordered_pairs`] on slices.
+#[derive(Debug)]
+pub struct SlicePairs<'a, T> {
+    slice: &'a [T],
+    diagonal: bool,
+    front: usize,
+    back: usize,
+    /// The lower items of the pairs from rank `front` up to the end of its row.
+    front_row: slice::Iter<'a, T>,
+    /// The higher position of the pair with rank `front`.
+    front_high: usize,
+    /// The positions of the pair with rank `back`.
+    back_cursor: (usize, usize),
+}
+
+impl<T> Clone for SlicePairs<'_, T> {
+    fn clone(&self) -> Self {
+        SlicePairs {
+            slice: self.slice,
+            diagonal: self.diagonal,
+            front: self.front,
+            back: self.back,
+            front_row: self.front_row.clone(),
+            front_high: self.front_high,
+            back_cursor: self.back_cursor,
+        }
+    }
+}
+
+impl<'a, T> SlicePairs<'a, T> {
+    fn new(slice: &'a [T], diagonal: bool) -> SlicePairs<'a, T> {
+        let back = if diagonal {
+            UnorderedPair::<usize>::pair_count_with_diagonal(slice.len())
+        } else {
+            UnorderedPair::<usize>::pair_count(slice.len())
+        }
+        .expect("number of pairs overflows usize");
+
+        let mut pairs = SlicePairs {
+            slice,
+            diagonal,
+            front: 0,
+            back,
+            front_row: [].iter(),
+            front_high: 0,
+            back_cursor: Self::unrank(diagonal, back),
+        };
+        pairs.seek_front(0);
+        pairs
+    }
+
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split_at(self, index: usize) -> (SlicePairs<'a, T>, SlicePairs<'a, T>) {
+        let mid = self.front + index;
+        let mut right = self.clone();
+        right.seek_front(mid);
+        let left = SlicePairs {
+            back: mid,
+            back_cursor: Self::unrank(self.diagonal, mid),
+            ..self
+        };
+        (left, right)
+    }
+
+    fn seek_front(&mut self, rank: usize) {
+        let (low, high) = Self::unrank(self.diagonal, rank);
+        let row_end = (high + usize::from(self.diagonal)).min(self.slice.len());
+        self.front = rank;
+        self.front_row = self.slice[low.min(row_end)..row_end].iter();
+        self.front_high = high;
+    }
+
+    fn unrank(diagonal: bool, rank: usize) -> (usize, usize) {
+        let UnorderedPair(low, high) = if diagonal {
+            UnorderedPair::<usize>::unrank_with_diagonal(rank)
+        } else {
+            UnorderedPair::<usize>::unrank(rank)
+        };
+        (low, high)
+    }
+
+    fn get(&self, (low, high): (usize, usize)) -> UnorderedPair<&'a T> {
+        UnorderedPair(&self.slice[low], &self.slice[high])
+    }
+}
+
+impl<'a, T> Iterator for SlicePairs<'a, T> {
+    type Item = UnorderedPair<&'a T>;
+
+    fn next(&mut self) -> Option<UnorderedPair<&'a T>> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+
+        let low = match self.front_row.next() {
+            Some(low) => low,
+            None => {
+                self.front_high += 1;
+                let row_end = self.front_high + usize::from(self.diagonal);
+                self.front_row = self.slice[..row_end].iter();
+                self.front_row.next()?
+            }
+        };
+        Some(UnorderedPair(low, &self.slice[self.front_high]))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<UnorderedPair<&'a T>> {
+        if n >= self.len() {
+            self.seek_front(self.back);
+            return None;
+        }
+        if n > 0 {
+            self.seek_front(self.front + n);
+        }
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    fn last(mut self) -> Option<UnorderedPair<&'a T>> {
+        self.next_back()
+    }
+}
+
+impl<T> DoubleEndedIterator for SlicePairs<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let (low, high) = &mut self.back_cursor;
+        if *low == 0 {
+            *high -= 1;
+            *low = if self.diagonal { *high } else { *high - 1 };
+        } else {
+            *low -= 1;
+        }
+        self.back -= 1;
+        Some(self.get(self.back_cursor))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len() {
+            self.back = self.front;
+            self.back_cursor = Self::unrank(self.diagonal, self.front);
+            return None;
+        }
+        if n > 0 {
+            self.back -= n;
+            self.back_cursor = Self::unrank(self.diagonal, self.back);
+        }
+        self.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for SlicePairs<'_, T> {
+    fn len(&self) -> usize {
+        self.back - self.front
+    }
+}
+
+impl<T> FusedIterator for SlicePairs<'_, T> {}
+
+/// An iterator over all unordered pairs of the items of another iterator.
+///
+/// This struct is created by [`IntoUnorderedPairs::unordered_pairs`] on iterators.
+#[derive(Debug, Clone)]
+pub struct IterPairs<I: Iterator> {
+    start: I,
+    rest: I,
+    index: usize,
+    current: Option<(I::Item, Take<I>)>,
+    diagonal: bool,
+}
+
+impl<I: Iterator + Clone> IterPairs<I> {
+    fn new(iter: I, diagonal: bool) -> IterPairs<I> {
+        IterPairs {
+            start: iter.clone(),
+            rest: iter,
+            index: 0,
+            current: None,
+            diagonal,
+        }
+    }
+}
+
+impl<I> Iterator for IterPairs<I>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+{
+    type Item = UnorderedPair<I::Item>;
+
+    fn next(&mut self) -> Option<UnorderedPair<I::Item>> {
+        loop {
+            if let Some((high, partners)) = &mut self.current {
+                if let Some(low) = partners.next() {
+                    return Some(UnorderedPair(low, high.clone()));
+                }
+            }
+
+            let high = self.rest.next()?;
+            let partner_count = self.index + usize::from(self.diagonal);
+            self.current = Some((high, self.start.clone().take(partner_count)));
+            self.index += 1;
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (rest_low, rest_high) = self.rest.size_hint();
+        let (current_low, current_high) = self
+            .current
+            .as_ref()
+            .map_or((0, Some(0)), |(_, partners)| partners.size_hint());
+        let pair_count = |n: usize| {
+            if self.diagonal {
+                UnorderedPair::<usize>::pair_count_with_diagonal(n)
+            } else {
+                UnorderedPair::<usize>::pair_count(n)
+            }
+        };
+        // The rows of the remaining items start at `self.index`.
+        let rest_count = |n: usize| {
+            let end = pair_count(self.index.checked_add(n)?)?;
+            Some(end - pair_count(self.index)?)
+        };
+
+        let low = rest_count(rest_low)
+            .and_then(|count| count.checked_add(current_low))
+            .unwrap_or(usize::MAX);
+        let high = rest_high
+            .and_then(rest_count)
+            .zip(current_high)
+            .and_then(|(rest, current)| rest.checked_add(current));
+        (low, high)
+    }
+}
+
+/// The length is exact as long as it is for the inner iterator.
+/// Like for every [`ExactSizeIterator`], `len` panics if the number of pairs overflows `usize`.
+impl<I> ExactSizeIterator for IterPairs<I>
+where
+    I: ExactSizeIterator + Clone,
+    I::Item: Clone,
+{
+}
+
+impl<I> FusedIterator for IterPairs<I>
+where
+    I: FusedIterator + Clone,
+    I::Item: Clone,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    fn nested_loop(n: usize, diagonal: bool) -> Vec<UnorderedPair<usize>> {
+        let mut pairs = Vec::new();
+        for high in 0..n {
+            for low in 0..high + usize::from(diagonal) {
+                pairs.push(UnorderedPair(low, high));
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn slice_pairs_match_nested_loop() {
+        let items: Vec<usize> = (0..7).collect();
+        for diagonal in [false, true] {
+            let pairs: Vec<_> = if diagonal {
+                items.unordered_pairs_with_diagonal()
+            } else {
+                items.unordered_pairs()
+            }
+            .map(|UnorderedPair(a, b)| UnorderedPair(*a, *b))
+            .collect();
+            assert_eq!(pairs, nested_loop(items.len(), diagonal));
+        }
+    }
+
+    #[test]
+    fn slice_pairs_double_ended() {
+        let items = [0, 1, 2, 3, 4];
+        let mut pairs = items.unordered_pairs();
+        assert_eq!(pairs.len(), 10);
+        assert_eq!(pairs.next_back(), Some(UnorderedPair(&3, &4)));
+        assert_eq!(pairs.nth(2), Some(UnorderedPair(&1, &2)));
+        assert_eq!(pairs.nth_back(1), Some(UnorderedPair(&1, &4)));
+        assert_eq!(pairs.len(), 4);
+        assert_eq!(pairs.rev().collect::<Vec<_>>().len(), 4);
+    }
+
+    #[test]
+    fn slice_pairs_mix_next_and_nth() {
+        let items: Vec<usize> = (0..9).collect();
+        for diagonal in [false, true] {
+            let expected = nested_loop(items.len(), diagonal);
+            let mut pairs = if diagonal {
+                items.unordered_pairs_with_diagonal()
+            } else {
+                items.unordered_pairs()
+            };
+            let mut front = 0;
+            let mut back = expected.len();
+            for step in 0..expected.len() {
+                let pair = match step % 4 {
+                    0 => pairs.next(),
+                    1 => pairs.nth(step % 3).inspect(|_| front += step % 3),
+                    2 => pairs.next_back(),
+                    _ => pairs.nth_back(step % 5).inspect(|_| back -= step % 5),
+                };
+                let Some(UnorderedPair(&a, &b)) = pair else {
+                    break;
+                };
+                if step % 4 < 2 {
+                    assert_eq!(UnorderedPair(a, b), expected[front]);
+                    front += 1;
+                } else {
+                    back -= 1;
+                    assert_eq!(UnorderedPair(a, b), expected[back]);
+                }
+                assert_eq!(pairs.len(), back - front);
+            }
+        }
+    }
+
+    #[test]
+    fn slice_pairs_nth_past_end() {
+        let items = [0, 1, 2];
+        let mut pairs = items.unordered_pairs();
+        assert_eq!(pairs.nth(3), None);
+        assert_eq!(pairs.next(), None);
+        assert_eq!(pairs.next_back(), None);
+    }
+
+    #[test]
+    fn slice_pairs_short_slices() {
+        assert_eq!([0; 0].unordered_pairs().count(), 0);
+        assert_eq!([0].unordered_pairs().count(), 0);
+        assert_eq!([0].unordered_pairs_with_diagonal().count(), 1);
+    }
+
+    #[test]
+    fn iter_pairs() {
+        let pairs: Vec<_> = (0..4).unordered_pairs().collect();
+        assert_eq!(
+            pairs,
+            [
+                UnorderedPair(0, 1),
+                UnorderedPair(0, 2),
+                UnorderedPair(1, 2),
+                UnorderedPair(0, 3),
+                UnorderedPair(1, 3),
+                UnorderedPair(2, 3)
+            ]
+        );
+        assert_eq!((0..4).unordered_pairs().size_hint(), (6, Some(6)));
+    }
+
+    #[test]
+    fn iter_pairs_with_diagonal() {
+        let mut pairs = (0..3).unordered_pairs_with_diagonal();
+        assert_eq!(pairs.size_hint(), (6, Some(6)));
+        assert_eq!(pairs.next(), Some(UnorderedPair(0, 0)));
+        assert_eq!(pairs.next(), Some(UnorderedPair(0, 1)));
+        assert_eq!(pairs.next(), Some(UnorderedPair(1, 1)));
+        assert_eq!(pairs.size_hint(), (3, Some(3)));
+        assert_eq!(pairs.count(), 3);
+    }
+
+    #[test]
+    fn iter_pairs_len() {
+        for diagonal in [false, true] {
+            let mut pairs = IterPairs::new([1, 2, 3, 4, 5].into_iter(), diagonal);
+            let mut remaining = if diagonal { 15 } else { 10 };
+            while pairs.len() > 0 {
+                assert_eq!(pairs.len(), remaining);
+                pairs.next();
+                remaining -= 1;
+            }
+            assert_eq!((remaining, pairs.next()), (0, None));
+        }
+    }
+
+    #[test]
+    fn slice_and_iter_pairs_have_the_same_order() {
+        let items: Vec<usize> = (0..6).collect();
+        let from_slice: Vec<_> = items
+            .unordered_pairs()
+            .map(|pair| pair.map(|&x| x))
+            .collect();
+        let from_iter: Vec<_> = items
+            .iter()
+            .unordered_pairs()
+            .map(|pair| pair.map(|&x| x))
+            .collect();
+        assert_eq!(from_slice, from_iter);
+
+        let from_slice: Vec<_> = items
+            .unordered_pairs_with_diagonal()
+            .map(|pair| pair.map(|&x| x))
+            .collect();
+        let from_iter: Vec<_> = items
+            .iter()
+            .unordered_pairs_with_diagonal()
+            .map(|pair| pair.map(|&x| x))
+            .collect();
+        assert_eq!(from_slice, from_iter);
+    }
+}
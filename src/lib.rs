@@ -1,6 +1,13 @@
 //! This crate provides a tuple struct for an unordered pair
 //! ## Crate Features
+//! - `std` (enabled by default): Enables the collections that are built on `HashMap`,
+//!   such as `UnorderedPairMap` and `UnorderedPairSet`, as well as `CommutativeHash`.
+//!   Without it, the crate is `#![no_std]`.
+//! - `alloc`: Enables the collections that only need an allocator, such as `SymmetricMatrix`.
+//!   Implied by `std`.
 //! - `serde`: Enables serde support for [`UnorderedPair`].
+//!   The `canonical` module serializes pairs with their components ordered smallest to largest.
+//! - `rayon`: Enables parallel iteration over all pairs of a slice, see `par::IntoParallelUnorderedPairs`.
 
 #![deny(
     rust_2018_idioms,
@@ -9,15 +16,200 @@
     clippy::doc_markdown,
     clippy::unimplemented
 )]
+#![no_std]
 
-use std::cmp::Ordering;
-use std::hash::{Hash, Hasher};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "std")]
+pub mod broad_phase;
+#[cfg(feature = "serde")]
+pub mod canonical;
+#[cfg(feature = "std")]
+pub mod closest;
+mod commutative;
+#[cfg(feature = "std")]
+pub mod contact;
+#[cfg(feature = "std")]
+pub mod disjoint_set;
+#[cfg(feature = "std")]
+pub mod dispatch;
+mod distinct;
+#[cfg(feature = "std")]
+pub mod graph;
+#[cfg(feature = "alloc")]
+pub mod interaction;
+pub mod iter;
+mod macros;
+#[cfg(feature = "std")]
+pub mod map;
+#[cfg(feature = "alloc")]
+pub mod matrix;
+#[cfg(feature = "rayon")]
+pub mod par;
+mod parse;
+mod rank;
+#[cfg(feature = "std")]
+pub mod set;
+mod sorted;
+#[cfg(feature = "std")]
+pub mod spatial_hash;
+mod string_key;
+
+#[cfg(feature = "std")]
+pub use broad_phase::SweepAndPrune;
+pub use commutative::hash_commutative;
+#[cfg(feature = "std")]
+pub use commutative::CommutativeHash;
+#[cfg(feature = "std")]
+pub use contact::{ContactEvent, ContactTracker};
+#[cfg(feature = "std")]
+pub use disjoint_set::DisjointSet;
+pub use distinct::{DistinctPair, NotDistinctError};
+#[cfg(feature = "std")]
+pub use graph::UnorderedEdgeGraph;
+#[cfg(feature = "alloc")]
+pub use interaction::InteractionMatrix;
+pub use iter::IntoUnorderedPairs;
+#[cfg(feature = "std")]
+pub use map::UnorderedPairMap;
+#[cfg(feature = "alloc")]
+pub use matrix::SymmetricMatrix;
+pub use parse::ParsePairError;
+#[cfg(feature = "std")]
+pub use set::UnorderedPairSet;
+pub use sorted::SortedPair;
+#[cfg(feature = "std")]
+pub use spatial_hash::SpatialHashGrid;
+pub use string_key::StringKey;
 
 /// A tuple struct representing an unordered pair
 #[derive(Debug, Copy, Clone, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnorderedPair<T>(pub T, pub T);
 
+impl<T> UnorderedPair<T> {
+    /// Returns `true` if either component is equal to `value`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unordered_pair::UnorderedPair;
+    ///
+    /// let pair = UnorderedPair(1, 2);
+    ///
+    /// assert!(pair.contains(&2));
+    /// assert!(!pair.contains(&3));
+    /// ```
+    pub fn contains(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.0 == *value || self.1 == *value
+    }
+
+    /// Returns the component that `value` is paired with,
+    /// or `None` if `value` isn't part of the pair.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unordered_pair::UnorderedPair;
+    ///
+    /// let pair = UnorderedPair(1, 2);
+    ///
+    /// assert_eq!(pair.other(&1), Some(&2));
+    /// assert_eq!(pair.other(&2), Some(&1));
+    /// assert_eq!(pair.other(&3), None);
+    /// ```
+    pub fn other(&self, value: &T) -> Option<&T>
+    where
+        T: PartialEq,
+    {
+        if self.0 == *value {
+            Some(&self.1)
+        } else if self.1 == *value {
+            Some(&self.0)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if both components are equal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unordered_pair::UnorderedPair;
+    ///
+    /// assert!(UnorderedPair(1, 1).is_loop());
+    /// assert!(!UnorderedPair(1, 2).is_loop());
+    /// ```
+    pub fn is_loop(&self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.0 == self.1
+    }
+
+    /// Applies `f` to both components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unordered_pair::UnorderedPair;
+    ///
+    /// let pair = UnorderedPair("a", "bc").map(str::len);
+    ///
+    /// assert_eq!(pair, UnorderedPair(2, 1));
+    /// ```
+    pub fn map<U, F>(self, mut f: F) -> UnorderedPair<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        UnorderedPair(f(self.0), f(self.1))
+    }
+
+    /// Converts from `&UnorderedPair<T>` to `UnorderedPair<&T>`.
+    pub fn as_ref(&self) -> UnorderedPair<&T> {
+        UnorderedPair(&self.0, &self.1)
+    }
+
+    /// Converts from `&mut UnorderedPair<T>` to `UnorderedPair<&mut T>`.
+    pub fn as_mut(&mut self) -> UnorderedPair<&mut T> {
+        UnorderedPair(&mut self.0, &mut self.1)
+    }
+
+    /// Swaps the components in place.
+    ///
+    /// The swapped pair is still equal to the original one.
+    pub fn swap(&mut self) {
+        core::mem::swap(&mut self.0, &mut self.1);
+    }
+
+    /// Returns the pair with its components swapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use unordered_pair::UnorderedPair;
+    ///
+    /// let swapped = UnorderedPair(1, 2).swapped();
+    ///
+    /// assert_eq!(swapped.0, 2);
+    /// assert_eq!(swapped, UnorderedPair(1, 2));
+    /// ```
+    pub fn swapped(self) -> UnorderedPair<T> {
+        UnorderedPair(self.1, self.0)
+    }
+}
+
 impl<T: Ord> UnorderedPair<T> {
     /// Transforms the `UnorderedPair<T>` into a `(T,T)`.
     /// The tuple's components are always in the same order, smallest to largest.
@@ -68,6 +260,66 @@ where
     }
 }
 
+/// Compares two pairs by their components ordered smallest to largest,
+/// so that the result agrees with the order-independent [`PartialEq`].
+///
+/// If the components of either pair are incomparable with each other,
+/// the pairs are only comparable if they are equal.
+impl<T> PartialOrd for UnorderedPair<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &UnorderedPair<T>) -> Option<Ordering> {
+        match (
+            self.partially_ordered_refs(),
+            other.partially_ordered_refs(),
+        ) {
+            (Some((self_min, self_max)), Some((other_min, other_max))) => {
+                match self_min.partial_cmp(other_min)? {
+                    Ordering::Equal => self_max.partial_cmp(other_max),
+                    ordering => Some(ordering),
+                }
+            }
+            _ if self == other => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+/// Compares two pairs by their components ordered smallest to largest,
+/// so that the result agrees with the order-independent [`PartialEq`].
+impl<T> Ord for UnorderedPair<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &UnorderedPair<T>) -> Ordering {
+        let (self_min, self_max) = self.ordered_refs();
+        let (other_min, other_max) = other.ordered_refs();
+
+        self_min
+            .cmp(other_min)
+            .then_with(|| self_max.cmp(other_max))
+    }
+}
+
+impl<T: PartialOrd> UnorderedPair<T> {
+    fn partially_ordered_refs(&self) -> Option<(&T, &T)> {
+        match self.0.partial_cmp(&self.1)? {
+            Ordering::Greater => Some((&self.1, &self.0)),
+            _ => Some((&self.0, &self.1)),
+        }
+    }
+}
+
+impl<T: Ord> UnorderedPair<T> {
+    fn ordered_refs(&self) -> (&T, &T) {
+        match self.0.cmp(&self.1) {
+            Ordering::Greater => (&self.1, &self.0),
+            _ => (&self.0, &self.1),
+        }
+    }
+}
+
 /// Computes the same hash regardless of the order of the contained items
 impl<T> Hash for UnorderedPair<T>
 where
@@ -92,6 +344,32 @@ where
     }
 }
 
+/// Writes the pair in set notation with its components ordered smallest to largest, e.g. `{1, 2}`
+///
+/// Formatting options such as the width are applied to each component.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::UnorderedPair;
+///
+/// assert_eq!(UnorderedPair(2, 1).to_string(), "{1, 2}");
+/// assert_eq!(format!("{:02}", UnorderedPair(7, 3)), "{03, 07}");
+/// ```
+impl<T> fmt::Display for UnorderedPair<T>
+where
+    T: fmt::Display + Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.ordered_refs();
+        f.write_str("{")?;
+        min.fmt(f)?;
+        f.write_str(", ")?;
+        max.fmt(f)?;
+        f.write_str("}")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +395,84 @@ mod tests {
         assert_ne!(pair1, pair2);
     }
 
+    #[test]
+    fn ord_different_internal_order() {
+        assert_eq!(
+            UnorderedPair(5, 7).cmp(&UnorderedPair(7, 5)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn ord_compares_smallest_first() {
+        assert!(UnorderedPair(9, 1) < UnorderedPair(2, 3));
+        assert!(UnorderedPair(3, 1) > UnorderedPair(1, 2));
+    }
+
+    #[test]
+    fn ord_btree_set_deduplicates() {
+        use std::collections::BTreeSet;
+
+        let set: BTreeSet<_> = [
+            UnorderedPair(1, 2),
+            UnorderedPair(2, 1),
+            UnorderedPair(0, 3),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn partial_ord_nan() {
+        let pair1 = UnorderedPair(f32::NAN, 1.3);
+        let pair2 = UnorderedPair(1.3, f32::NAN);
+        assert_eq!(pair1.partial_cmp(&pair2), None);
+        assert_eq!(pair1.partial_cmp(&UnorderedPair(0.0, 1.0)), None);
+    }
+
+    #[test]
+    fn partial_ord_incomparable_components() {
+        #[derive(Debug, PartialEq)]
+        struct Incomparable(u8);
+
+        impl PartialOrd for Incomparable {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                (self == other).then_some(Ordering::Equal)
+            }
+        }
+
+        let pair = UnorderedPair(Incomparable(1), Incomparable(2));
+        let rev = UnorderedPair(Incomparable(2), Incomparable(1));
+        let other = UnorderedPair(Incomparable(1), Incomparable(1));
+        assert_eq!(pair.partial_cmp(&rev), Some(Ordering::Equal));
+        assert_eq!(pair.partial_cmp(&other), None);
+    }
+
+    #[test]
+    fn other_of_loop() {
+        let pair = UnorderedPair(4, 4);
+        assert_eq!(pair.other(&4), Some(&4));
+        assert!(pair.is_loop());
+    }
+
+    #[test]
+    fn as_mut_modifies_components() {
+        let mut pair = UnorderedPair(1, 2);
+        let UnorderedPair(first, second) = pair.as_mut();
+        *first += 10;
+        *second += 20;
+        assert_eq!(pair, UnorderedPair(11, 22));
+    }
+
+    #[test]
+    fn swap_in_place() {
+        let mut pair = UnorderedPair("a", "b");
+        pair.swap();
+        assert_eq!((pair.0, pair.1), ("b", "a"));
+        assert_eq!(pair.as_ref().map(|s| s.len()), UnorderedPair(1, 1));
+    }
+
     #[test]
     fn hash_different_internal_order() {
         use std::collections::hash_map::DefaultHasher as Hasher;
@@ -0,0 +1,174 @@
+/// Matches an [`UnorderedPair`](crate::UnorderedPair) against patterns regardless of the order of its components
+///
+/// Each arm of the form `(first, second) => body` matches the pair in either order.
+/// The components are bound in the order the arm declares them,
+/// so `first` binds the component matching the first pattern even if it is stored second.
+/// Arms may have `if` guards, which are evaluated with the same bindings.
+///
+/// Any other pattern, such as `_`, matches the pair as a whole and can be used as a fallback.
+/// Without a fallback, the arms have to cover all combinations, each in at least one order.
+/// The arms are tried from top to bottom, and for each arm the declared order is tried first.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{match_unordered, UnorderedPair};
+///
+/// #[derive(Debug, Clone, Copy)]
+/// enum Shape {
+///     Circle(f64),
+///     Square(f64),
+/// }
+///
+/// fn describe(pair: UnorderedPair<Shape>) -> String {
+///     match_unordered!(pair, {
+///         (Shape::Circle(radius), Shape::Square(side)) if radius * 2.0 > side => {
+///             format!("circle with radius {radius} sticks out of square with side {side}")
+///         }
+///         (Shape::Circle(radius), Shape::Square(side)) => {
+///             format!("circle with radius {radius} fits into square with side {side}")
+///         }
+///         (Shape::Circle(_), Shape::Circle(_)) => "two circles".to_string(),
+///         (Shape::Square(_), Shape::Square(_)) => "two squares".to_string(),
+///     })
+/// }
+///
+/// assert_eq!(
+///     describe(UnorderedPair(Shape::Square(1.0), Shape::Circle(0.25))),
+///     "circle with radius 0.25 fits into square with side 1"
+/// );
+/// assert_eq!(
+///     describe(UnorderedPair(Shape::Circle(1.0), Shape::Square(1.0))),
+///     "circle with radius 1 sticks out of square with side 1"
+/// );
+/// ```
+#[macro_export]
+macro_rules! match_unordered {
+    ($pair:expr, { $($arms:tt)* }) => {
+        $crate::__match_unordered_arms!(@munch ($pair) [] $($arms)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_unordered_arms {
+    (@munch ($pair:expr) [$($out:tt)*]) => {
+        match $pair {
+            $($out)*
+        }
+    };
+
+    (@munch ($pair:expr) [$($out:tt)*]
+        ($first:pat, $second:pat) $(if $guard:expr)? => $body:block $(,)? $($rest:tt)*
+    ) => {
+        $crate::__match_unordered_arms!(@munch ($pair) [
+            $($out)*
+            $crate::UnorderedPair($first, $second) $(if $guard)? => $body,
+            #[allow(unreachable_patterns)]
+            $crate::UnorderedPair($second, $first) $(if $guard)? => $body,
+        ] $($rest)*)
+    };
+
+    (@munch ($pair:expr) [$($out:tt)*]
+        ($first:pat, $second:pat) $(if $guard:expr)? => $body:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::__match_unordered_arms!(@munch ($pair) [
+            $($out)*
+            $crate::UnorderedPair($first, $second) $(if $guard)? => $body,
+            #[allow(unreachable_patterns)]
+            $crate::UnorderedPair($second, $first) $(if $guard)? => $body,
+        ] $($($rest)*)?)
+    };
+
+    (@munch ($pair:expr) [$($out:tt)*]
+        $fallback:pat $(if $guard:expr)? => $body:block $(,)? $($rest:tt)*
+    ) => {
+        $crate::__match_unordered_arms!(@munch ($pair) [
+            $($out)*
+            $fallback $(if $guard)? => $body,
+        ] $($rest)*)
+    };
+
+    (@munch ($pair:expr) [$($out:tt)*]
+        $fallback:pat $(if $guard:expr)? => $body:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::__match_unordered_arms!(@munch ($pair) [
+            $($out)*
+            $fallback $(if $guard)? => $body,
+        ] $($($rest)*)?)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UnorderedPair;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Kind {
+        Ball(u32),
+        Wall,
+        Sensor,
+    }
+
+    fn classify(pair: UnorderedPair<Kind>) -> (&'static str, u32) {
+        match_unordered!(pair, {
+            (Kind::Ball(a), Kind::Ball(b)) => ("balls", a * 10 + b),
+            (Kind::Ball(speed), Kind::Wall) if speed > 5 => ("break", speed),
+            (Kind::Ball(speed), Kind::Wall) => ("bounce", speed),
+            (Kind::Sensor, _) => ("sensor", 0),
+            _ => ("ignore", 0),
+        })
+    }
+
+    #[test]
+    fn binds_in_declared_order() {
+        assert_eq!(
+            classify(UnorderedPair(Kind::Wall, Kind::Ball(3))),
+            ("bounce", 3)
+        );
+        assert_eq!(
+            classify(UnorderedPair(Kind::Ball(3), Kind::Wall)),
+            ("bounce", 3)
+        );
+        assert_eq!(
+            classify(UnorderedPair(Kind::Wall, Kind::Ball(9))),
+            ("break", 9)
+        );
+        assert_eq!(
+            classify(UnorderedPair(Kind::Ball(1), Kind::Ball(2))),
+            ("balls", 12)
+        );
+        assert_eq!(
+            classify(UnorderedPair(Kind::Wall, Kind::Sensor)),
+            ("sensor", 0)
+        );
+        assert_eq!(
+            classify(UnorderedPair(Kind::Wall, Kind::Wall)),
+            ("ignore", 0)
+        );
+    }
+
+    #[test]
+    fn exhaustive_without_fallback() {
+        let total = |pair: UnorderedPair<bool>| {
+            match_unordered!(pair, {
+                (true, true) => 2,
+                (true, false) => { 1 }
+                (false, false) => 0
+            })
+        };
+        assert_eq!(total(UnorderedPair(false, true)), 1);
+        assert_eq!(total(UnorderedPair(true, true)), 2);
+        assert_eq!(total(UnorderedPair(false, false)), 0);
+    }
+
+    #[test]
+    fn matches_references() {
+        let pair = UnorderedPair(Some(4), None);
+        let value = match_unordered!(pair.as_ref(), {
+            (Some(value), None) => *value,
+            _ => 0,
+        });
+        assert_eq!(value, 4);
+    }
+}
@@ -0,0 +1,612 @@
+//! A map keyed by unordered pairs, see [`UnorderedPairMap`].
+
+use crate::UnorderedPair;
+use std::borrow::Borrow;
+use std::collections::hash_map::{self, HashMap, RandomState};
+use std::collections::{hash_set, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::FusedIterator;
+use std::vec::Vec;
+
+/// A hash map keyed by [`UnorderedPair<K>`]
+///
+/// Entries can be looked up with both components in either order without constructing an owned key.
+/// The map also keeps an index of the pairs each component is part of,
+/// which makes it cheap to query or remove all entries touching a single component.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{UnorderedPair, UnorderedPairMap};
+///
+/// let mut distances = UnorderedPairMap::new();
+/// distances.insert("a", "b", 3);
+/// distances.insert("c", "a", 4);
+///
+/// assert_eq!(distances.get(&"b", &"a"), Some(&3));
+/// assert_eq!(distances.degree(&"a"), 2);
+///
+/// let removed = distances.remove_all_touching(&"a");
+/// assert_eq!(removed.len(), 2);
+/// assert!(distances.is_empty());
+/// ```
+#[derive(Clone)]
+pub struct UnorderedPairMap<K, V, S = RandomState> {
+    entries: HashMap<UnorderedPair<K>, V, S>,
+    neighbors: HashMap<K, HashSet<K, S>, S>,
+}
+
+impl<K, V> UnorderedPairMap<K, V, RandomState> {
+    /// Creates an empty `UnorderedPairMap`.
+    pub fn new() -> UnorderedPairMap<K, V, RandomState> {
+        UnorderedPairMap::default()
+    }
+}
+
+impl<K, V, S: Clone> UnorderedPairMap<K, V, S> {
+    /// Creates an empty `UnorderedPairMap` which will use the given hash builder to hash keys.
+    pub fn with_hasher(hash_builder: S) -> UnorderedPairMap<K, V, S> {
+        UnorderedPairMap {
+            entries: HashMap::with_hasher(hash_builder.clone()),
+            neighbors: HashMap::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<K, V, S> UnorderedPairMap<K, V, S> {
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all entries from the map.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.neighbors.clear();
+    }
+
+    /// An iterator visiting all pair-value pairs in arbitrary order.
+    pub fn iter(&self) -> hash_map::Iter<'_, UnorderedPair<K>, V> {
+        self.entries.iter()
+    }
+
+    /// An iterator visiting all pair-value pairs in arbitrary order,
+    /// with mutable references to the values.
+    pub fn iter_mut(&mut self) -> hash_map::IterMut<'_, UnorderedPair<K>, V> {
+        self.entries.iter_mut()
+    }
+
+    /// An iterator visiting all pairs in arbitrary order.
+    pub fn keys(&self) -> hash_map::Keys<'_, UnorderedPair<K>, V> {
+        self.entries.keys()
+    }
+
+    /// An iterator visiting all values in arbitrary order.
+    pub fn values(&self) -> hash_map::Values<'_, UnorderedPair<K>, V> {
+        self.entries.values()
+    }
+
+    /// An iterator visiting all values mutably in arbitrary order.
+    pub fn values_mut(&mut self) -> hash_map::ValuesMut<'_, UnorderedPair<K>, V> {
+        self.entries.values_mut()
+    }
+}
+
+impl<K, V, S> UnorderedPairMap<K, V, S>
+where
+    K: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Inserts a value for the pair `{a, b}`, returning the previous value if there was one.
+    pub fn insert(&mut self, a: K, b: K, value: V) -> Option<V> {
+        match self.entry(a, b) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Gets the entry for the pair `{a, b}` for in-place manipulation.
+    pub fn entry(&mut self, a: K, b: K) -> Entry<'_, K, V, S> {
+        match self.entries.entry(UnorderedPair(a, b)) {
+            hash_map::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry {
+                inner,
+                neighbors: &mut self.neighbors,
+            }),
+            hash_map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry {
+                inner,
+                neighbors: &mut self.neighbors,
+            }),
+        }
+    }
+
+    /// Returns a reference to the value of the pair `{a, b}`.
+    pub fn get(&self, a: &K, b: &K) -> Option<&V> {
+        self.entries.get(&UnorderedPair(a, b) as &dyn PairKey<K>)
+    }
+
+    /// Returns a mutable reference to the value of the pair `{a, b}`.
+    pub fn get_mut(&mut self, a: &K, b: &K) -> Option<&mut V> {
+        self.entries
+            .get_mut(&UnorderedPair(a, b) as &dyn PairKey<K>)
+    }
+
+    /// Returns `true` if the map contains a value for the pair `{a, b}`.
+    pub fn contains_pair(&self, a: &K, b: &K) -> bool {
+        self.entries
+            .contains_key(&UnorderedPair(a, b) as &dyn PairKey<K>)
+    }
+
+    /// Removes the pair `{a, b}` from the map, returning its value if it was present.
+    pub fn remove(&mut self, a: &K, b: &K) -> Option<V> {
+        self.remove_entry(a, b).map(|(_, value)| value)
+    }
+
+    /// Removes the pair `{a, b}` from the map, returning the stored pair and its value.
+    pub fn remove_entry(&mut self, a: &K, b: &K) -> Option<(UnorderedPair<K>, V)> {
+        let (pair, value) = self
+            .entries
+            .remove_entry(&UnorderedPair(a, b) as &dyn PairKey<K>)?;
+        unlink(&mut self.neighbors, &pair);
+        Some((pair, value))
+    }
+
+    /// Removes every entry whose pair contains `element`, returning the removed entries.
+    pub fn remove_all_touching(&mut self, element: &K) -> Vec<(UnorderedPair<K>, V)> {
+        let Some(partners) = self.neighbors.remove(element) else {
+            return Vec::new();
+        };
+
+        partners
+            .into_iter()
+            .filter_map(|partner| {
+                let entry = self
+                    .entries
+                    .remove_entry(&UnorderedPair(element, &partner) as &dyn PairKey<K>);
+                if let Some(partners_of_partner) = self.neighbors.get_mut(&partner) {
+                    partners_of_partner.remove(element);
+                    if partners_of_partner.is_empty() {
+                        self.neighbors.remove(&partner);
+                    }
+                }
+                entry
+            })
+            .collect()
+    }
+
+    /// An iterator visiting every element that `element` is paired with, in arbitrary order.
+    ///
+    /// If the map contains the pair `{element, element}`, `element` itself is visited as well.
+    pub fn neighbors(&self, element: &K) -> Neighbors<'_, K> {
+        Neighbors {
+            inner: self.neighbors.get(element).map(HashSet::iter),
+        }
+    }
+
+    /// Returns the number of pairs that contain `element`.
+    pub fn degree(&self, element: &K) -> usize {
+        self.neighbors.get(element).map_or(0, HashSet::len)
+    }
+}
+
+fn unlink<K, S>(neighbors: &mut HashMap<K, HashSet<K, S>, S>, pair: &UnorderedPair<K>)
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    let UnorderedPair(first, second) = pair;
+
+    for (element, partner) in [(first, second), (second, first)] {
+        if let Some(partners) = neighbors.get_mut(element) {
+            partners.remove(partner);
+            if partners.is_empty() {
+                neighbors.remove(element);
+            }
+        }
+    }
+}
+
+fn link<K, S>(neighbors: &mut HashMap<K, HashSet<K, S>, S>, pair: &UnorderedPair<K>)
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher + Clone,
+{
+    let UnorderedPair(first, second) = pair;
+    let hash_builder = neighbors.hasher().clone();
+
+    for (element, partner) in [(first, second), (second, first)] {
+        neighbors
+            .entry(element.clone())
+            .or_insert_with(|| HashSet::with_hasher(hash_builder.clone()))
+            .insert(partner.clone());
+    }
+}
+
+impl<K, V, S: Default> Default for UnorderedPairMap<K, V, S> {
+    fn default() -> UnorderedPairMap<K, V, S> {
+        UnorderedPairMap {
+            entries: HashMap::default(),
+            neighbors: HashMap::default(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for UnorderedPairMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.entries.iter()).finish()
+    }
+}
+
+impl<K, V, S> PartialEq for UnorderedPairMap<K, V, S>
+where
+    K: Ord + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &UnorderedPairMap<K, V, S>) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<K, V, S> Eq for UnorderedPairMap<K, V, S>
+where
+    K: Ord + Hash,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K, V, S> Extend<(UnorderedPair<K>, V)> for UnorderedPairMap<K, V, S>
+where
+    K: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    fn extend<I: IntoIterator<Item = (UnorderedPair<K>, V)>>(&mut self, iter: I) {
+        for (UnorderedPair(a, b), value) in iter {
+            self.insert(a, b, value);
+        }
+    }
+}
+
+impl<K, V, S> FromIterator<(UnorderedPair<K>, V)> for UnorderedPairMap<K, V, S>
+where
+    K: Ord + Hash + Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (UnorderedPair<K>, V)>>(iter: I) -> Self {
+        let mut map = UnorderedPairMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> IntoIterator for UnorderedPairMap<K, V, S> {
+    type Item = (UnorderedPair<K>, V);
+    type IntoIter = hash_map::IntoIter<UnorderedPair<K>, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a UnorderedPairMap<K, V, S> {
+    type Item = (&'a UnorderedPair<K>, &'a V);
+    type IntoIter = hash_map::Iter<'a, UnorderedPair<K>, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut UnorderedPairMap<K, V, S> {
+    type Item = (&'a UnorderedPair<K>, &'a mut V);
+    type IntoIter = hash_map::IterMut<'a, UnorderedPair<K>, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the elements paired with a given element.
+///
+/// This struct is created by [`UnorderedPairMap::neighbors`].
+#[derive(Debug, Clone)]
+pub struct Neighbors<'a, K> {
+    inner: Option<hash_set::Iter<'a, K>>,
+}
+
+impl<'a, K> Iterator for Neighbors<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.inner.as_mut()?.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner
+            .as_ref()
+            .map_or((0, Some(0)), Iterator::size_hint)
+    }
+}
+
+impl<K> ExactSizeIterator for Neighbors<'_, K> {}
+
+impl<K> FusedIterator for Neighbors<'_, K> {}
+
+/// A view into a single entry of an [`UnorderedPairMap`], which may either be vacant or occupied.
+///
+/// This enum is created by [`UnorderedPairMap::entry`].
+#[derive(Debug)]
+pub enum Entry<'a, K, V, S> {
+    /// An occupied entry.
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    /// A vacant entry.
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Returns a reference to this entry's pair.
+    pub fn key(&self) -> &UnorderedPair<K> {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Ensures a value is in the entry by inserting `default` if empty,
+    /// and returns a mutable reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Ensures a value is in the entry by inserting the result of `default` if empty,
+    /// and returns a mutable reference to the value.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Provides in-place mutable access to an occupied entry before any potential inserts.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Entry<'a, K, V, S> {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Default,
+    S: BuildHasher + Clone,
+{
+    /// Ensures a value is in the entry by inserting the default value if empty,
+    /// and returns a mutable reference to the value.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+/// A view into an occupied entry in an [`UnorderedPairMap`]. It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, K, V, S> {
+    inner: hash_map::OccupiedEntry<'a, UnorderedPair<K>, V>,
+    neighbors: &'a mut HashMap<K, HashSet<K, S>, S>,
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns a reference to this entry's pair.
+    pub fn key(&self) -> &UnorderedPair<K> {
+        self.inner.key()
+    }
+
+    /// Returns a reference to the value in the entry.
+    pub fn get(&self) -> &V {
+        self.inner.get()
+    }
+
+    /// Returns a mutable reference to the value in the entry.
+    pub fn get_mut(&mut self) -> &mut V {
+        self.inner.get_mut()
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    pub fn into_mut(self) -> &'a mut V {
+        self.inner.into_mut()
+    }
+
+    /// Sets the value of the entry, returning the previous value.
+    pub fn insert(&mut self, value: V) -> V {
+        self.inner.insert(value)
+    }
+
+    /// Takes the value out of the entry, returning it.
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    /// Takes the pair and the value out of the entry, returning both.
+    pub fn remove_entry(self) -> (UnorderedPair<K>, V) {
+        let (pair, value) = self.inner.remove_entry();
+        unlink(self.neighbors, &pair);
+        (pair, value)
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for OccupiedEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", self.inner.key())
+            .field("value", self.inner.get())
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in an [`UnorderedPairMap`]. It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, K, V, S> {
+    inner: hash_map::VacantEntry<'a, UnorderedPair<K>, V>,
+    neighbors: &'a mut HashMap<K, HashSet<K, S>, S>,
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S>
+where
+    K: Hash + Eq + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Returns a reference to the pair that would be used when inserting a value.
+    pub fn key(&self) -> &UnorderedPair<K> {
+        self.inner.key()
+    }
+
+    /// Takes ownership of the pair.
+    pub fn into_key(self) -> UnorderedPair<K> {
+        self.inner.into_key()
+    }
+
+    /// Sets the value of the entry, and returns a mutable reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        link(self.neighbors, self.inner.key());
+        self.inner.insert(value)
+    }
+}
+
+impl<K: fmt::Debug, V, S> fmt::Debug for VacantEntry<'_, K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry")
+            .field(self.inner.key())
+            .finish()
+    }
+}
+
+/// Allows looking up an `UnorderedPair<K>` using borrowed components.
+pub(crate) trait PairKey<K> {
+    fn components(&self) -> (&K, &K);
+}
+
+impl<K> PairKey<K> for UnorderedPair<K> {
+    fn components(&self) -> (&K, &K) {
+        (&self.0, &self.1)
+    }
+}
+
+impl<K> PairKey<K> for UnorderedPair<&K> {
+    fn components(&self) -> (&K, &K) {
+        (self.0, self.1)
+    }
+}
+
+impl<'a, K: 'a> Borrow<dyn PairKey<K> + 'a> for UnorderedPair<K> {
+    fn borrow(&self) -> &(dyn PairKey<K> + 'a) {
+        self
+    }
+}
+
+impl<K: PartialEq> PartialEq for dyn PairKey<K> + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        UnorderedPair::from(self.components()) == UnorderedPair::from(other.components())
+    }
+}
+
+impl<K: Eq> Eq for dyn PairKey<K> + '_ {}
+
+/// Hashes exactly like the `UnorderedPair<K>` it stands in for
+impl<K: Ord + Hash> Hash for dyn PairKey<K> + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        UnorderedPair::from(self.components()).hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::String;
+
+    #[test]
+    fn get_in_either_order() {
+        let mut map = UnorderedPairMap::new();
+        map.insert(String::from("a"), String::from("b"), 1);
+
+        let (a, b) = (String::from("a"), String::from("b"));
+        assert_eq!(map.get(&a, &b), Some(&1));
+        assert_eq!(map.get(&b, &a), Some(&1));
+        assert_eq!(map.get(&a, &a), None);
+    }
+
+    #[test]
+    fn insert_replaces_reversed_pair() {
+        let mut map = UnorderedPairMap::new();
+        assert_eq!(map.insert(1, 2, 'x'), None);
+        assert_eq!(map.insert(2, 1, 'y'), Some('x'));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.degree(&1), 1);
+    }
+
+    #[test]
+    fn entry_updates_index() {
+        let mut map = UnorderedPairMap::new();
+        *map.entry(1, 2).or_insert(0) += 1;
+        *map.entry(2, 1).or_insert(0) += 1;
+        assert_eq!(map.get(&1, &2), Some(&2));
+        assert_eq!(map.neighbors(&2).collect::<Vec<_>>(), [&1]);
+
+        match map.entry(2, 1) {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+            Entry::Vacant(_) => panic!("entry should be occupied"),
+        }
+        assert_eq!(map.degree(&1), 0);
+        assert_eq!(map.neighbors(&2).count(), 0);
+    }
+
+    #[test]
+    fn remove_all_touching() {
+        let mut map: UnorderedPairMap<_, _> = [
+            (UnorderedPair(1, 2), 'a'),
+            (UnorderedPair(3, 1), 'b'),
+            (UnorderedPair(1, 1), 'c'),
+            (UnorderedPair(2, 3), 'd'),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut removed = map.remove_all_touching(&1);
+        removed.sort();
+        assert_eq!(
+            removed,
+            [
+                (UnorderedPair(1, 1), 'c'),
+                (UnorderedPair(1, 2), 'a'),
+                (UnorderedPair(3, 1), 'b')
+            ]
+        );
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.degree(&1), 0);
+        assert_eq!(map.neighbors(&2).collect::<Vec<_>>(), [&3]);
+    }
+
+    #[test]
+    fn self_pair_is_its_own_neighbor() {
+        let mut map = UnorderedPairMap::new();
+        map.insert(4, 4, ());
+        assert_eq!(map.neighbors(&4).collect::<Vec<_>>(), [&4]);
+        assert_eq!(map.remove(&4, &4), Some(()));
+        assert_eq!(map.degree(&4), 0);
+    }
+}
@@ -0,0 +1,411 @@
+//! A symmetric matrix that stores only its lower triangle, see [`SymmetricMatrix`].
+
+use crate::UnorderedPair;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::{Index, IndexMut, Range};
+
+/// Whether a [`SymmetricMatrix`] stores the cells where row and column are equal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Diagonal {
+    /// The diagonal is stored and can be indexed with `UnorderedPair(i, i)`.
+    Included,
+    /// The diagonal is not stored. Indexing it panics.
+    Excluded,
+}
+
+/// A square matrix where the cell at `(i, j)` is always the same as the cell at `(j, i)`
+///
+/// Only the lower triangle is stored, row by row, which halves the memory of a dense matrix.
+/// Cells are indexed with an [`UnorderedPair<usize>`], so both orders of the indices refer to the same cell.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::matrix::Diagonal;
+/// use unordered_pair::{SymmetricMatrix, UnorderedPair};
+///
+/// let mut distances = SymmetricMatrix::new(3, Diagonal::Excluded, 0);
+/// distances[UnorderedPair(0, 2)] = 5;
+///
+/// assert_eq!(distances[UnorderedPair(2, 0)], 5);
+/// assert_eq!(distances.get(UnorderedPair(1, 1)), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SymmetricMatrix<T> {
+    size: usize,
+    diagonal: Diagonal,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> SymmetricMatrix<T> {
+    /// Creates a matrix with `size` rows and columns, with every cell set to `value`.
+    pub fn new(size: usize, diagonal: Diagonal, value: T) -> SymmetricMatrix<T> {
+        SymmetricMatrix {
+            size,
+            diagonal,
+            cells: vec![value; triangle_len(size, diagonal)],
+        }
+    }
+
+    /// Resizes the matrix to `size` rows and columns.
+    ///
+    /// When growing, new cells are set to `value`. When shrinking, the cells of the removed rows are dropped.
+    pub fn resize(&mut self, size: usize, value: T) {
+        self.cells.resize(triangle_len(size, self.diagonal), value);
+        self.size = size;
+    }
+
+    /// Creates a dense matrix, row by row.
+    ///
+    /// Cells that aren't stored, i.e. the diagonal if it is [`Diagonal::Excluded`], are set to `fill`.
+    pub fn to_dense(&self, fill: T) -> Vec<Vec<T>> {
+        (0..self.size)
+            .map(|row| {
+                (0..self.size)
+                    .map(|column| {
+                        self.get(UnorderedPair(row, column))
+                            .unwrap_or(&fill)
+                            .clone()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<T> SymmetricMatrix<T> {
+    /// Creates a matrix with `size` rows and columns, computing each cell from its indices.
+    ///
+    /// `f` is called once per stored cell, row by row.
+    pub fn from_fn<F>(size: usize, diagonal: Diagonal, mut f: F) -> SymmetricMatrix<T>
+    where
+        F: FnMut(UnorderedPair<usize>) -> T,
+    {
+        let cells = (0..size)
+            .flat_map(|row| {
+                (0..row_len(row, diagonal)).map(move |column| UnorderedPair(row, column))
+            })
+            .map(&mut f)
+            .collect();
+
+        SymmetricMatrix {
+            size,
+            diagonal,
+            cells,
+        }
+    }
+
+    /// Creates a matrix from a dense, square matrix given row by row.
+    ///
+    /// Only the lower triangle (and the diagonal if it is [`Diagonal::Included`]) is kept,
+    /// the remaining cells are dropped without checking that the input is symmetric.
+    pub fn from_dense(
+        dense: Vec<Vec<T>>,
+        diagonal: Diagonal,
+    ) -> Result<SymmetricMatrix<T>, NotSquareError> {
+        let size = dense.len();
+
+        if let Some((row, columns)) = dense
+            .iter()
+            .enumerate()
+            .find(|(_, columns)| columns.len() != size)
+        {
+            return Err(NotSquareError {
+                row,
+                len: columns.len(),
+                expected: size,
+            });
+        }
+
+        let cells = dense
+            .into_iter()
+            .enumerate()
+            .flat_map(|(row, columns)| columns.into_iter().take(row_len(row, diagonal)))
+            .collect();
+
+        Ok(SymmetricMatrix {
+            size,
+            diagonal,
+            cells,
+        })
+    }
+
+    /// Creates a matrix with a row and column for each item, computing each cell from
+    /// the two items in parallel.
+    ///
+    /// The cells are split evenly across threads.
+    #[cfg(feature = "rayon")]
+    pub fn par_pairwise<U, F>(items: &[U], diagonal: Diagonal, f: F) -> SymmetricMatrix<T>
+    where
+        T: Send,
+        U: Sync,
+        F: Fn(&U, &U) -> T + Sync,
+    {
+        use crate::par::IntoParallelUnorderedPairs;
+        use rayon::iter::ParallelIterator;
+
+        let pairs = match diagonal {
+            Diagonal::Included => items.par_unordered_pairs_with_diagonal(),
+            Diagonal::Excluded => items.par_unordered_pairs(),
+        };
+
+        SymmetricMatrix {
+            size: items.len(),
+            diagonal,
+            cells: pairs.map(|UnorderedPair(a, b)| f(a, b)).collect(),
+        }
+    }
+
+    /// Returns the number of rows, which is the same as the number of columns.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns whether the diagonal is stored.
+    pub fn diagonal(&self) -> Diagonal {
+        self.diagonal
+    }
+
+    /// Returns a reference to the cell at `pair`,
+    /// or `None` if it is out of bounds or on a diagonal that isn't stored.
+    pub fn get(&self, pair: UnorderedPair<usize>) -> Option<&T> {
+        self.cell_index(pair).map(|index| &self.cells[index])
+    }
+
+    /// Returns a mutable reference to the cell at `pair`,
+    /// or `None` if it is out of bounds or on a diagonal that isn't stored.
+    pub fn get_mut(&mut self, pair: UnorderedPair<usize>) -> Option<&mut T> {
+        self.cell_index(pair).map(|index| &mut self.cells[index])
+    }
+
+    /// Adds a row and column to the matrix, computing the new cells from the index of the other row.
+    ///
+    /// Returns the index of the new row.
+    pub fn push_with<F>(&mut self, mut f: F) -> usize
+    where
+        F: FnMut(usize) -> T,
+    {
+        let row = self.size;
+        self.cells
+            .extend((0..row_len(row, self.diagonal)).map(&mut f));
+        self.size += 1;
+        row
+    }
+
+    /// An iterator over the cells of the row `row`, together with their column index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is out of bounds.
+    pub fn row(&self, row: usize) -> Row<'_, T> {
+        assert!(row < self.size, "row {row} is out of bounds");
+        Row {
+            matrix: self,
+            row,
+            columns: 0..self.size,
+        }
+    }
+
+    /// An iterator over all rows.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = Row<'_, T>> + '_ {
+        (0..self.size).map(|row| self.row(row))
+    }
+
+    /// An iterator over all stored cells, together with their indices, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (UnorderedPair<usize>, &T)> + '_ {
+        (0..self.size)
+            .flat_map(|row| {
+                (0..row_len(row, self.diagonal)).map(move |column| UnorderedPair(row, column))
+            })
+            .zip(&self.cells)
+    }
+
+    /// Returns the stored cells, row by row.
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Returns the stored cells mutably, row by row.
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    fn cell_index(&self, pair: UnorderedPair<usize>) -> Option<usize> {
+        if pair.0 >= self.size || pair.1 >= self.size {
+            return None;
+        }
+
+        match self.diagonal {
+            Diagonal::Included => pair.rank_with_diagonal(),
+            Diagonal::Excluded => pair.rank(),
+        }
+    }
+}
+
+fn row_len(row: usize, diagonal: Diagonal) -> usize {
+    match diagonal {
+        Diagonal::Included => row + 1,
+        Diagonal::Excluded => row,
+    }
+}
+
+fn triangle_len(size: usize, diagonal: Diagonal) -> usize {
+    let len = match diagonal {
+        Diagonal::Included => UnorderedPair::<usize>::pair_count_with_diagonal(size),
+        Diagonal::Excluded => UnorderedPair::<usize>::pair_count(size),
+    };
+    len.expect("capacity overflow")
+}
+
+impl<T> TryFrom<Vec<Vec<T>>> for SymmetricMatrix<T> {
+    type Error = NotSquareError;
+
+    /// Creates a matrix with [`Diagonal::Included`], see [`SymmetricMatrix::from_dense`].
+    fn try_from(dense: Vec<Vec<T>>) -> Result<SymmetricMatrix<T>, NotSquareError> {
+        SymmetricMatrix::from_dense(dense, Diagonal::Included)
+    }
+}
+
+impl<T> Index<UnorderedPair<usize>> for SymmetricMatrix<T> {
+    type Output = T;
+
+    fn index(&self, pair: UnorderedPair<usize>) -> &T {
+        match self.get(pair) {
+            Some(cell) => cell,
+            None => panic!("{pair:?} is out of bounds"),
+        }
+    }
+}
+
+impl<T> IndexMut<UnorderedPair<usize>> for SymmetricMatrix<T> {
+    fn index_mut(&mut self, pair: UnorderedPair<usize>) -> &mut T {
+        match self.get_mut(pair) {
+            Some(cell) => cell,
+            None => panic!("{pair:?} is out of bounds"),
+        }
+    }
+}
+
+/// An iterator over the cells of a single row of a [`SymmetricMatrix`].
+///
+/// This struct is created by [`SymmetricMatrix::row`].
+#[derive(Debug, Clone)]
+pub struct Row<'a, T> {
+    matrix: &'a SymmetricMatrix<T>,
+    row: usize,
+    columns: Range<usize>,
+}
+
+impl<'a, T> Iterator for Row<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<(usize, &'a T)> {
+        let matrix = self.matrix;
+        let row = self.row;
+        self.columns
+            .find_map(|column| Some((column, matrix.get(UnorderedPair(row, column))?)))
+    }
+}
+
+impl<T> FusedIterator for Row<'_, T> {}
+
+/// The error returned by [`SymmetricMatrix::from_dense`] when the rows have different lengths
+/// than the number of rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotSquareError {
+    row: usize,
+    len: usize,
+    expected: usize,
+}
+
+impl NotSquareError {
+    /// Returns the index of the first row with the wrong length.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+}
+
+impl fmt::Display for NotSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "row {} has {} columns, expected {}",
+            self.row, self.len, self.expected
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NotSquareError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_in_either_order() {
+        let mut matrix = SymmetricMatrix::new(4, Diagonal::Included, 0);
+        matrix[UnorderedPair(3, 1)] = 7;
+        matrix[UnorderedPair(2, 2)] = 5;
+        assert_eq!(matrix[UnorderedPair(1, 3)], 7);
+        assert_eq!(matrix[UnorderedPair(2, 2)], 5);
+        assert_eq!(matrix.as_slice().iter().sum::<i32>(), 12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_excluded_diagonal() {
+        let matrix = SymmetricMatrix::new(4, Diagonal::Excluded, 0);
+        let _ = matrix[UnorderedPair(2, 2)];
+    }
+
+    #[test]
+    fn from_fn_matches_indices() {
+        for diagonal in [Diagonal::Included, Diagonal::Excluded] {
+            let matrix = SymmetricMatrix::from_fn(5, diagonal, |pair| pair.into_ordered_tuple());
+            for (pair, cell) in matrix.iter() {
+                assert_eq!(&pair.into_ordered_tuple(), cell);
+                assert_eq!(matrix.get(pair), Some(cell));
+            }
+        }
+    }
+
+    #[test]
+    fn row_visits_every_column() {
+        let matrix = SymmetricMatrix::from_fn(3, Diagonal::Excluded, |UnorderedPair(a, b)| a + b);
+        assert_eq!(matrix.row(1).collect::<Vec<_>>(), [(0, &1), (2, &3)]);
+        assert_eq!(matrix.rows().len(), 3);
+    }
+
+    #[test]
+    fn push_and_resize() {
+        let mut matrix = SymmetricMatrix::new(0, Diagonal::Excluded, 0);
+        assert_eq!(matrix.push_with(|_| 1), 0);
+        assert_eq!(matrix.push_with(|other| other + 10), 1);
+        assert_eq!(matrix[UnorderedPair(0, 1)], 10);
+
+        matrix.resize(3, 2);
+        assert_eq!(matrix[UnorderedPair(2, 1)], 2);
+        matrix.resize(1, 2);
+        assert!(matrix.as_slice().is_empty());
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let dense = vec![vec![0, 1, 2], vec![1, 0, 3], vec![2, 3, 0]];
+        let matrix = SymmetricMatrix::try_from(dense.clone()).unwrap();
+        assert_eq!(matrix.to_dense(9), dense);
+
+        let matrix = SymmetricMatrix::from_dense(dense, Diagonal::Excluded).unwrap();
+        assert_eq!(matrix.to_dense(9), [[9, 1, 2], [1, 9, 3], [2, 3, 9]]);
+    }
+
+    #[test]
+    fn from_dense_not_square() {
+        let error = SymmetricMatrix::from_dense(vec![vec![1, 2], vec![3]], Diagonal::Included);
+        assert_eq!(error.unwrap_err().row(), 1);
+    }
+}
@@ -0,0 +1,169 @@
+//! Parallel iterators over all unordered pairs of a slice, see [`IntoParallelUnorderedPairs`].
+
+use crate::iter::{IntoUnorderedPairs, SlicePairs};
+use crate::UnorderedPair;
+use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback, UnindexedConsumer};
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+/// An extension trait for iterating over every 2-combination of a slice in parallel
+///
+/// This is the parallel counterpart of [`IntoUnorderedPairs`] on slices.
+/// The work is split by the [rank](UnorderedPair::rank) of the pairs,
+/// so each thread receives the same number of pairs no matter where in the triangle they are.
+///
+/// # Examples
+///
+/// ```
+/// use rayon::prelude::*;
+/// use unordered_pair::par::IntoParallelUnorderedPairs;
+///
+/// let positions = [1.0, 4.0, 6.0f64];
+/// let total: f64 = positions
+///     .par_unordered_pairs()
+///     .map(|pair| (pair.0 - pair.1).abs())
+///     .sum();
+///
+/// assert_eq!(total, 10.0);
+/// ```
+pub trait IntoParallelUnorderedPairs<T: Sync> {
+    /// Returns a parallel iterator over every pair of distinct positions.
+    fn par_unordered_pairs(&self) -> ParSlicePairs<'_, T>;
+
+    /// Returns a parallel iterator over every pair of positions, including self-pairs such as `{a, a}`.
+    fn par_unordered_pairs_with_diagonal(&self) -> ParSlicePairs<'_, T>;
+}
+
+impl<T: Sync> IntoParallelUnorderedPairs<T> for [T] {
+    fn par_unordered_pairs(&self) -> ParSlicePairs<'_, T> {
+        ParSlicePairs {
+            pairs: self.unordered_pairs(),
+        }
+    }
+
+    fn par_unordered_pairs_with_diagonal(&self) -> ParSlicePairs<'_, T> {
+        ParSlicePairs {
+            pairs: self.unordered_pairs_with_diagonal(),
+        }
+    }
+}
+
+/// A parallel iterator over all unordered pairs of a slice.
+///
+/// Pairs are produced in the same order as by [`SlicePairs`].
+///
+/// This struct is created by [`IntoParallelUnorderedPairs::par_unordered_pairs`].
+#[derive(Debug)]
+pub struct ParSlicePairs<'a, T> {
+    pairs: SlicePairs<'a, T>,
+}
+
+impl<T> Clone for ParSlicePairs<'_, T> {
+    fn clone(&self) -> Self {
+        ParSlicePairs {
+            pairs: self.pairs.clone(),
+        }
+    }
+}
+
+impl<'a, T: Sync> ParallelIterator for ParSlicePairs<'a, T> {
+    type Item = UnorderedPair<&'a T>;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.pairs.len())
+    }
+}
+
+impl<T: Sync> IndexedParallelIterator for ParSlicePairs<'_, T> {
+    fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        callback.callback(PairsProducer { pairs: self.pairs })
+    }
+}
+
+struct PairsProducer<'a, T> {
+    pairs: SlicePairs<'a, T>,
+}
+
+impl<'a, T: Sync> Producer for PairsProducer<'a, T> {
+    type Item = UnorderedPair<&'a T>;
+    type IntoIter = SlicePairs<'a, T>;
+
+    fn into_iter(self) -> SlicePairs<'a, T> {
+        self.pairs
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.pairs.split_at(index);
+        (
+            PairsProducer { pairs: left },
+            PairsProducer { pairs: right },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix::Diagonal;
+    use crate::SymmetricMatrix;
+    use std::vec::Vec;
+
+    #[test]
+    fn par_pairs_match_sequential_pairs() {
+        let items: Vec<u32> = (0..100).collect();
+        let sequential: Vec<_> = items.unordered_pairs().collect();
+        let parallel: Vec<_> = items.par_unordered_pairs().with_max_len(7).collect();
+        assert_eq!(parallel, sequential);
+
+        let sequential: Vec<_> = items.unordered_pairs_with_diagonal().collect();
+        let parallel: Vec<_> = items
+            .par_unordered_pairs_with_diagonal()
+            .with_max_len(7)
+            .collect();
+        assert_eq!(parallel, sequential);
+
+        let reversed: Vec<_> = items
+            .par_unordered_pairs_with_diagonal()
+            .with_max_len(7)
+            .rev()
+            .collect();
+        assert!(reversed.iter().eq(sequential.iter().rev()));
+    }
+
+    #[test]
+    fn par_pairs_rev_and_len() {
+        let items = [1, 2, 3, 4];
+        assert_eq!(items.par_unordered_pairs().len(), 6);
+        let reversed: Vec<_> = items.par_unordered_pairs().rev().collect();
+        assert_eq!(reversed[0], UnorderedPair(&3, &4));
+    }
+
+    #[test]
+    fn par_pairwise_fills_matrix() {
+        let items = [1, 5, 8];
+        let matrix = SymmetricMatrix::par_pairwise(&items, Diagonal::Included, |a, b| a * b);
+        assert_eq!(matrix[UnorderedPair(2, 1)], 40);
+        assert_eq!(matrix[UnorderedPair(0, 0)], 1);
+        assert_eq!(matrix.size(), 3);
+    }
+}
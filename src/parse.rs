@@ -0,0 +1,140 @@
+use crate::UnorderedPair;
+use core::fmt;
+use core::str::FromStr;
+
+/// An error which can be returned when parsing a pair from a string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePairError<E> {
+    /// The input doesn't contain the delimiter between the two components.
+    MissingDelimiter,
+    /// The input starts with an opening bracket without the matching closing bracket, or vice versa.
+    UnmatchedBracket,
+    /// The first component couldn't be parsed.
+    First(E),
+    /// The second component couldn't be parsed.
+    Second(E),
+}
+
+impl<E: fmt::Display> fmt::Display for ParsePairError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePairError::MissingDelimiter => {
+                f.write_str("missing delimiter between the components")
+            }
+            ParsePairError::UnmatchedBracket => f.write_str("unmatched bracket around the pair"),
+            ParsePairError::First(error) => write!(f, "invalid first component: {error}"),
+            ParsePairError::Second(error) => write!(f, "invalid second component: {error}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for ParsePairError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParsePairError::MissingDelimiter | ParsePairError::UnmatchedBracket => None,
+            ParsePairError::First(error) | ParsePairError::Second(error) => Some(error),
+        }
+    }
+}
+
+/// Parses a pair written as `{a, b}`, `(a, b)` or `a,b`
+///
+/// Whitespace around the components is ignored.
+/// The input is split at the first comma,
+/// so the first component must not contain a comma.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{ParsePairError, UnorderedPair};
+///
+/// assert_eq!("{1, 2}".parse(), Ok(UnorderedPair(2, 1)));
+/// assert_eq!("(1, 2)".parse(), Ok(UnorderedPair(1, 2)));
+/// assert_eq!("1,2".parse(), Ok(UnorderedPair(1, 2)));
+///
+/// let error = "{1, x}".parse::<UnorderedPair<u8>>().unwrap_err();
+/// assert!(matches!(error, ParsePairError::Second(_)));
+/// ```
+impl<T: FromStr> FromStr for UnorderedPair<T> {
+    type Err = ParsePairError<T::Err>;
+
+    fn from_str(input: &str) -> Result<UnorderedPair<T>, Self::Err> {
+        let input = input.trim();
+        let inner = match (input.chars().next(), input.chars().next_back()) {
+            (Some('{'), Some('}')) | (Some('('), Some(')')) if input.len() >= 2 => {
+                &input[1..input.len() - 1]
+            }
+            (Some('{' | '('), _) | (_, Some('}' | ')')) => {
+                return Err(ParsePairError::UnmatchedBracket)
+            }
+            _ => input,
+        };
+        parse_components(inner, ',', true)
+    }
+}
+
+/// Parses two components separated by the first occurrence of `delimiter`,
+/// optionally trimming whitespace around each component.
+pub(crate) fn parse_components<T: FromStr>(
+    input: &str,
+    delimiter: char,
+    trim: bool,
+) -> Result<UnorderedPair<T>, ParsePairError<T::Err>> {
+    let (first, second) = input
+        .split_once(delimiter)
+        .ok_or(ParsePairError::MissingDelimiter)?;
+    let (first, second) = if trim {
+        (first.trim(), second.trim())
+    } else {
+        (first, second)
+    };
+
+    Ok(UnorderedPair(
+        first.parse().map_err(ParsePairError::First)?,
+        second.parse().map_err(ParsePairError::Second)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn parse_accepts_all_notations() {
+        for input in ["{3, 7}", "(7, 3)", "7,3", "  { 3 ,7 }  "] {
+            assert_eq!(input.parse(), Ok(UnorderedPair(3u8, 7)), "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "{3, 7)".parse::<UnorderedPair<u8>>(),
+            Err(ParsePairError::UnmatchedBracket)
+        );
+        assert_eq!(
+            "{".parse::<UnorderedPair<u8>>(),
+            Err(ParsePairError::UnmatchedBracket)
+        );
+        assert_eq!(
+            "{3}".parse::<UnorderedPair<u8>>(),
+            Err(ParsePairError::MissingDelimiter)
+        );
+        let error = "{x, 7}".parse::<UnorderedPair<u8>>().unwrap_err();
+        assert!(matches!(error, ParsePairError::First(_)));
+        assert_eq!(
+            error.to_string(),
+            "invalid first component: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let pair = UnorderedPair(String::from("b"), String::from("a"));
+        let text = pair.to_string();
+        assert_eq!(text, "{a, b}");
+        assert_eq!(text.parse(), Ok(pair));
+    }
+}
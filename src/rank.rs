@@ -0,0 +1,290 @@
+use crate::UnorderedPair;
+
+macro_rules! impl_rank {
+    ($($t:ty),*) => {$(
+        impl UnorderedPair<$t> {
+            /// Maps a pair of distinct indices to a unique, dense index.
+            ///
+            /// Pairs are ranked in colexicographic order: `{0, 1}, {0, 2}, {1, 2}, {0, 3}, ...`.
+            /// The pairs of indices in `0..n` are therefore ranked `0..Self::pair_count(n)`,
+            /// independent of `n`.
+            ///
+            /// Returns `None` if both indices are equal or if the rank doesn't fit in the index type.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use unordered_pair::UnorderedPair;
+            ///
+            #[doc = concat!("let pair = UnorderedPair::<", stringify!($t), ">(3, 1);")]
+            ///
+            /// assert_eq!(pair.rank(), Some(4));
+            #[doc = concat!("assert_eq!(UnorderedPair::<", stringify!($t), ">::unrank(4), pair);")]
+            #[doc = concat!("assert_eq!(UnorderedPair::<", stringify!($t), ">(1, 1).rank(), None);")]
+            /// ```
+            pub const fn rank(self) -> Option<$t> {
+                let (low, high) = Self::ordered(self);
+                if low == high {
+                    return None;
+                }
+                match Self::pair_count(high) {
+                    Some(offset) => offset.checked_add(low),
+                    None => None,
+                }
+            }
+
+            /// Maps a pair of indices, which may be equal, to a unique, dense index.
+            ///
+            /// Pairs are ranked in colexicographic order: `{0, 0}, {0, 1}, {1, 1}, {0, 2}, ...`.
+            /// The pairs of indices in `0..n` are therefore ranked `0..Self::pair_count_with_diagonal(n)`,
+            /// independent of `n`.
+            ///
+            /// Returns `None` if the rank doesn't fit in the index type.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// use unordered_pair::UnorderedPair;
+            ///
+            #[doc = concat!("let pair = UnorderedPair::<", stringify!($t), ">(2, 1);")]
+            ///
+            /// assert_eq!(pair.rank_with_diagonal(), Some(4));
+            #[doc = concat!("assert_eq!(UnorderedPair::<", stringify!($t), ">::unrank_with_diagonal(4), pair);")]
+            /// ```
+            pub const fn rank_with_diagonal(self) -> Option<$t> {
+                let (low, high) = Self::ordered(self);
+                match Self::pair_count_with_diagonal(high) {
+                    Some(offset) => offset.checked_add(low),
+                    None => None,
+                }
+            }
+
+            /// The inverse of [`rank`](Self::rank).
+            ///
+            /// The returned pair has the smaller index first.
+            pub const fn unrank(rank: $t) -> Self {
+                // The largest `high` with `high * (high - 1) / 2 <= rank` is
+                // `(1 + sqrt(8 * rank + 1)) / 2`, as long as that doesn't overflow.
+                let high = match Self::checked_discriminant(rank) {
+                    Some(discriminant) => (Self::sqrt_floor(discriminant) + 1) / 2,
+                    None => {
+                        let mut low = 1;
+                        let mut high = <$t>::MAX;
+                        while low < high {
+                            let mid = high - (high - low) / 2;
+                            match Self::pair_count(mid) {
+                                Some(offset) if offset <= rank => low = mid,
+                                _ => high = mid - 1,
+                            }
+                        }
+                        low
+                    }
+                };
+                match Self::pair_count(high) {
+                    Some(offset) => UnorderedPair(rank - offset, high),
+                    None => unreachable!(),
+                }
+            }
+
+            /// The inverse of [`rank_with_diagonal`](Self::rank_with_diagonal).
+            ///
+            /// The returned pair has the smaller index first.
+            pub const fn unrank_with_diagonal(rank: $t) -> Self {
+                // The largest `high` with `high * (high + 1) / 2 <= rank` is
+                // `(sqrt(8 * rank + 1) - 1) / 2`, as long as that doesn't overflow.
+                let high = match Self::checked_discriminant(rank) {
+                    Some(discriminant) => (Self::sqrt_floor(discriminant) - 1) / 2,
+                    None => {
+                        let mut low = 0;
+                        let mut high = <$t>::MAX;
+                        while low < high {
+                            let mid = high - (high - low) / 2;
+                            match Self::pair_count_with_diagonal(mid) {
+                                Some(offset) if offset <= rank => low = mid,
+                                _ => high = mid - 1,
+                            }
+                        }
+                        low
+                    }
+                };
+                match Self::pair_count_with_diagonal(high) {
+                    Some(offset) => UnorderedPair(rank - offset, high),
+                    None => unreachable!(),
+                }
+            }
+
+            /// Returns the number of pairs of distinct indices in `0..n`, i.e. `n * (n - 1) / 2`.
+            ///
+            /// Returns `None` if the count doesn't fit in the index type.
+            pub const fn pair_count(n: $t) -> Option<$t> {
+                if n % 2 == 0 {
+                    (n / 2).checked_mul(n.saturating_sub(1))
+                } else {
+                    n.checked_mul((n - 1) / 2)
+                }
+            }
+
+            /// Returns the number of pairs of indices in `0..n`, including pairs of equal indices,
+            /// i.e. `n * (n + 1) / 2`.
+            ///
+            /// Returns `None` if the count doesn't fit in the index type.
+            pub const fn pair_count_with_diagonal(n: $t) -> Option<$t> {
+                if n % 2 == 0 {
+                    match n.checked_add(1) {
+                        Some(next) => (n / 2).checked_mul(next),
+                        None => None,
+                    }
+                } else {
+                    n.checked_mul(n / 2 + 1)
+                }
+            }
+
+            /// Returns the square root of `n` rounded down, computed digit by digit.
+            const fn sqrt_floor(n: $t) -> $t {
+                let mut remainder = n;
+                let mut root: $t = 0;
+                let mut bit: $t = 1 << (<$t>::BITS - 2);
+                while bit > remainder {
+                    bit >>= 2;
+                }
+                while bit != 0 {
+                    if remainder >= root + bit {
+                        remainder -= root + bit;
+                        root = (root >> 1) + bit;
+                    } else {
+                        root >>= 1;
+                    }
+                    bit >>= 2;
+                }
+                root
+            }
+
+            const fn checked_discriminant(rank: $t) -> Option<$t> {
+                match rank.checked_mul(8) {
+                    Some(product) => product.checked_add(1),
+                    None => None,
+                }
+            }
+
+            const fn ordered(self) -> ($t, $t) {
+                if self.0 > self.1 {
+                    (self.1, self.0)
+                } else {
+                    (self.0, self.1)
+                }
+            }
+        }
+    )*};
+}
+
+impl_rank!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sqrt_floor_matches_definition() {
+        for n in 0..=u8::MAX {
+            let root = UnorderedPair::<u8>::sqrt_floor(n);
+            assert!(u16::from(root).pow(2) <= u16::from(n));
+            assert!((u16::from(root) + 1).pow(2) > u16::from(n));
+        }
+        assert_eq!(
+            UnorderedPair::<u64>::sqrt_floor(u64::MAX),
+            u64::from(u32::MAX)
+        );
+        assert_eq!(
+            UnorderedPair::<u128>::sqrt_floor(u128::MAX),
+            u128::from(u64::MAX)
+        );
+        assert_eq!(UnorderedPair::<u32>::sqrt_floor(65535 * 65535), 65535);
+        assert_eq!(UnorderedPair::<u32>::sqrt_floor(65535 * 65535 - 1), 65534);
+    }
+
+    #[test]
+    fn rank_is_dense_and_colexicographic() {
+        let mut expected = 0;
+        for high in 0..20usize {
+            for low in 0..high {
+                assert_eq!(UnorderedPair::<usize>(high, low).rank(), Some(expected));
+                assert_eq!(
+                    UnorderedPair::<usize>::unrank(expected),
+                    UnorderedPair(low, high)
+                );
+                expected += 1;
+            }
+            assert_eq!(UnorderedPair::<usize>::pair_count(high + 1), Some(expected));
+        }
+    }
+
+    #[test]
+    fn rank_with_diagonal_is_dense_and_colexicographic() {
+        let mut expected = 0;
+        for high in 0..20usize {
+            for low in 0..=high {
+                assert_eq!(
+                    UnorderedPair::<usize>(low, high).rank_with_diagonal(),
+                    Some(expected)
+                );
+                assert_eq!(
+                    UnorderedPair::<usize>::unrank_with_diagonal(expected),
+                    UnorderedPair(low, high)
+                );
+                expected += 1;
+            }
+            assert_eq!(
+                UnorderedPair::<usize>::pair_count_with_diagonal(high + 1),
+                Some(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn rank_overflow() {
+        assert_eq!(UnorderedPair(0u8, 23).rank(), Some(253));
+        assert_eq!(UnorderedPair(2u8, 23).rank(), Some(255));
+        assert_eq!(UnorderedPair(3u8, 23).rank(), None);
+        assert_eq!(UnorderedPair(0u8, 255).rank(), None);
+        assert_eq!(UnorderedPair(u64::MAX, 0).rank_with_diagonal(), None);
+        assert_eq!(UnorderedPair::<u8>::pair_count_with_diagonal(u8::MAX), None);
+    }
+
+    #[test]
+    fn unrank_full_range() {
+        for rank in [0, 1, u8::MAX - 1, u8::MAX] {
+            assert_eq!(UnorderedPair::<u8>::unrank(rank).rank(), Some(rank));
+            assert_eq!(
+                UnorderedPair::<u8>::unrank_with_diagonal(rank).rank_with_diagonal(),
+                Some(rank)
+            );
+        }
+        for rank in [u128::MAX - 1, u128::MAX] {
+            assert_eq!(UnorderedPair::<u128>::unrank(rank).rank(), Some(rank));
+            assert_eq!(
+                UnorderedPair::<u128>::unrank_with_diagonal(rank).rank_with_diagonal(),
+                Some(rank)
+            );
+        }
+    }
+
+    #[test]
+    fn unrank_around_fallback() {
+        let threshold = u32::MAX / 8;
+        for rank in threshold - 2..threshold + 2 {
+            assert_eq!(UnorderedPair::<u32>::unrank(rank).rank(), Some(rank));
+            assert_eq!(
+                UnorderedPair::<u32>::unrank_with_diagonal(rank).rank_with_diagonal(),
+                Some(rank)
+            );
+        }
+    }
+
+    #[test]
+    fn rank_in_const_context() {
+        const RANK: Option<u32> = UnorderedPair::<u32>(5, 2).rank();
+        const PAIR: UnorderedPair<u32> = UnorderedPair::<u32>::unrank(12);
+        assert_eq!(RANK, Some(12));
+        assert_eq!(PAIR, UnorderedPair(2, 5));
+    }
+}
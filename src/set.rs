@@ -0,0 +1,290 @@
+//! A set of unordered pairs, see [`UnorderedPairSet`].
+
+use crate::map::{Neighbors, UnorderedPairMap};
+use crate::UnorderedPair;
+use std::collections::hash_map::{self, RandomState};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::vec::Vec;
+
+/// A hash set of [`UnorderedPair<T>`]
+///
+/// Like [`UnorderedPairMap`], which it is built on, the set keeps an index of
+/// the pairs each element is part of. This makes it cheap to ask which elements
+/// are paired with a given element or to remove every pair touching it.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::UnorderedPairSet;
+///
+/// let mut contacts = UnorderedPairSet::new();
+/// contacts.insert(1, 2);
+/// contacts.insert(3, 1);
+///
+/// assert!(contacts.contains(&2, &1));
+/// assert_eq!(contacts.degree(&1), 2);
+///
+/// contacts.remove_all_touching(&1);
+/// assert!(contacts.is_empty());
+/// ```
+#[derive(Clone)]
+pub struct UnorderedPairSet<T, S = RandomState> {
+    map: UnorderedPairMap<T, (), S>,
+}
+
+impl<T> UnorderedPairSet<T, RandomState> {
+    /// Creates an empty `UnorderedPairSet`.
+    pub fn new() -> UnorderedPairSet<T, RandomState> {
+        UnorderedPairSet::default()
+    }
+}
+
+impl<T, S: Clone> UnorderedPairSet<T, S> {
+    /// Creates an empty `UnorderedPairSet` which will use the given hash builder to hash elements.
+    pub fn with_hasher(hash_builder: S) -> UnorderedPairSet<T, S> {
+        UnorderedPairSet {
+            map: UnorderedPairMap::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<T, S> UnorderedPairSet<T, S> {
+    /// Returns the number of pairs in the set.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the set contains no pairs.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Removes all pairs from the set.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// An iterator visiting all pairs in arbitrary order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+}
+
+impl<T, S> UnorderedPairSet<T, S>
+where
+    T: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    /// Adds the pair `{a, b}` to the set, returning `true` if it wasn't present yet.
+    pub fn insert(&mut self, a: T, b: T) -> bool {
+        self.map.insert(a, b, ()).is_none()
+    }
+
+    /// Returns `true` if the set contains the pair `{a, b}`.
+    pub fn contains(&self, a: &T, b: &T) -> bool {
+        self.map.contains_pair(a, b)
+    }
+
+    /// Removes the pair `{a, b}` from the set, returning `true` if it was present.
+    pub fn remove(&mut self, a: &T, b: &T) -> bool {
+        self.map.remove(a, b).is_some()
+    }
+
+    /// Removes the pair `{a, b}` from the set, returning the stored pair if it was present.
+    pub fn take(&mut self, a: &T, b: &T) -> Option<UnorderedPair<T>> {
+        self.map.remove_entry(a, b).map(|(pair, ())| pair)
+    }
+
+    /// Removes every pair containing `element`, returning the removed pairs.
+    pub fn remove_all_touching(&mut self, element: &T) -> Vec<UnorderedPair<T>> {
+        self.map
+            .remove_all_touching(element)
+            .into_iter()
+            .map(|(pair, ())| pair)
+            .collect()
+    }
+
+    /// An iterator visiting every element that `element` is paired with, in arbitrary order.
+    ///
+    /// If the set contains the pair `{element, element}`, `element` itself is visited as well.
+    pub fn neighbors(&self, element: &T) -> Neighbors<'_, T> {
+        self.map.neighbors(element)
+    }
+
+    /// Returns the number of pairs that contain `element`.
+    pub fn degree(&self, element: &T) -> usize {
+        self.map.degree(element)
+    }
+}
+
+impl<T, S: Default> Default for UnorderedPairSet<T, S> {
+    fn default() -> UnorderedPairSet<T, S> {
+        UnorderedPairSet {
+            map: UnorderedPairMap::default(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for UnorderedPairSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T, S> PartialEq for UnorderedPairSet<T, S>
+where
+    T: Ord + Hash,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &UnorderedPairSet<T, S>) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T, S> Eq for UnorderedPairSet<T, S>
+where
+    T: Ord + Hash,
+    S: BuildHasher,
+{
+}
+
+impl<T, S> Extend<UnorderedPair<T>> for UnorderedPairSet<T, S>
+where
+    T: Ord + Hash + Clone,
+    S: BuildHasher + Clone,
+{
+    fn extend<I: IntoIterator<Item = UnorderedPair<T>>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|pair| (pair, ())));
+    }
+}
+
+impl<T, S> FromIterator<UnorderedPair<T>> for UnorderedPairSet<T, S>
+where
+    T: Ord + Hash + Clone,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = UnorderedPair<T>>>(iter: I) -> Self {
+        let mut set = UnorderedPairSet::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, S> IntoIterator for UnorderedPairSet<T, S> {
+    type Item = UnorderedPair<T>;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter {
+            inner: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a UnorderedPairSet<T, S> {
+    type Item = &'a UnorderedPair<T>;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// An iterator over the pairs of an [`UnorderedPairSet`].
+///
+/// This struct is created by [`UnorderedPairSet::iter`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a, T> {
+    inner: hash_map::Keys<'a, UnorderedPair<T>, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a UnorderedPair<T>;
+
+    fn next(&mut self) -> Option<&'a UnorderedPair<T>> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+/// An owning iterator over the pairs of an [`UnorderedPairSet`].
+///
+/// This struct is created by the `into_iter` method on [`UnorderedPairSet`].
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    inner: hash_map::IntoIter<UnorderedPair<T>, ()>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = UnorderedPair<T>;
+
+    fn next(&mut self) -> Option<UnorderedPair<T>> {
+        self.inner.next().map(|(pair, ())| pair)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_contains_in_either_order() {
+        let mut set = UnorderedPairSet::new();
+        assert!(set.insert(1, 2));
+        assert!(!set.insert(2, 1));
+        assert!(set.contains(&2, &1));
+        assert!(!set.contains(&1, &1));
+        assert_eq!(set.len(), 1);
+    }
+
+    #[test]
+    fn neighbors_and_degree() {
+        let set: UnorderedPairSet<_> = [
+            UnorderedPair(1, 2),
+            UnorderedPair(3, 1),
+            UnorderedPair(2, 3),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut neighbors: Vec<_> = set.neighbors(&1).copied().collect();
+        neighbors.sort();
+        assert_eq!(neighbors, [2, 3]);
+        assert_eq!(set.degree(&3), 2);
+        assert_eq!(set.degree(&4), 0);
+    }
+
+    #[test]
+    fn remove_keeps_index_in_sync() {
+        let mut set = UnorderedPairSet::new();
+        set.insert(1, 2);
+        set.insert(1, 3);
+        assert!(set.remove(&2, &1));
+        assert!(!set.remove(&2, &1));
+        assert_eq!(set.neighbors(&1).collect::<Vec<_>>(), [&3]);
+        assert_eq!(set.degree(&2), 0);
+
+        assert_eq!(set.remove_all_touching(&3), [UnorderedPair(1, 3)]);
+        assert!(set.is_empty());
+        assert_eq!(set.degree(&1), 0);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::UnorderedPair;
+
+/// A pair whose components are stored smallest to largest
+///
+/// The order is normalized once on construction. Because of this, `Hash`, `Eq`
+/// and `Ord` are derived structurally and don't need to compare the components again.
+///
+/// A `SortedPair<T>` hashes exactly like the equivalent [`UnorderedPair<T>`].
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{SortedPair, UnorderedPair};
+///
+/// let pair = SortedPair::new(7, 5);
+///
+/// assert_eq!(pair.min_element(), &5);
+/// assert_eq!(pair.max_element(), &7);
+/// assert_eq!(UnorderedPair::from(pair), UnorderedPair(7, 5));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "UnorderedPair<T>",
+        bound(deserialize = "T: serde::Deserialize<'de> + Ord")
+    )
+)]
+pub struct SortedPair<T>(T, T);
+
+impl<T: Ord> SortedPair<T> {
+    /// Creates a new `SortedPair<T>`, putting the smaller component first.
+    pub fn new(first: T, second: T) -> SortedPair<T> {
+        if first > second {
+            SortedPair(second, first)
+        } else {
+            SortedPair(first, second)
+        }
+    }
+}
+
+impl<T> SortedPair<T> {
+    /// Returns a reference to the smaller component.
+    pub fn min_element(&self) -> &T {
+        &self.0
+    }
+
+    /// Returns a reference to the larger component.
+    pub fn max_element(&self) -> &T {
+        &self.1
+    }
+
+    /// Returns references to both components, smallest to largest.
+    pub fn as_tuple(&self) -> (&T, &T) {
+        (&self.0, &self.1)
+    }
+
+    /// Transforms the `SortedPair<T>` into a `(T, T)`, smallest to largest.
+    pub fn into_tuple(self) -> (T, T) {
+        (self.0, self.1)
+    }
+}
+
+impl<T: Ord> From<UnorderedPair<T>> for SortedPair<T> {
+    fn from(pair: UnorderedPair<T>) -> SortedPair<T> {
+        SortedPair::new(pair.0, pair.1)
+    }
+}
+
+impl<T: Ord> From<(T, T)> for SortedPair<T> {
+    fn from(tuple: (T, T)) -> SortedPair<T> {
+        SortedPair::new(tuple.0, tuple.1)
+    }
+}
+
+impl<T> From<SortedPair<T>> for UnorderedPair<T> {
+    fn from(pair: SortedPair<T>) -> UnorderedPair<T> {
+        UnorderedPair(pair.0, pair.1)
+    }
+}
+
+impl<T> From<SortedPair<T>> for (T, T) {
+    fn from(pair: SortedPair<T>) -> (T, T) {
+        pair.into_tuple()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{Hash, Hasher};
+
+    fn hash_of(value: &impl Hash) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn new_normalizes_order() {
+        assert_eq!(SortedPair::new(7, 5), SortedPair::new(5, 7));
+        assert_eq!(SortedPair::new(7, 5).into_tuple(), (5, 7));
+    }
+
+    #[test]
+    fn hash_matches_unordered_pair() {
+        assert_eq!(
+            hash_of(&SortedPair::new(7, 5)),
+            hash_of(&UnorderedPair(7, 5))
+        );
+        assert_eq!(
+            hash_of(&SortedPair::new(7, 5)),
+            hash_of(&UnorderedPair(5, 7))
+        );
+    }
+
+    #[test]
+    fn round_trips_through_unordered_pair() {
+        let pair = UnorderedPair(9, 2);
+        let sorted = SortedPair::from(pair);
+        assert_eq!(UnorderedPair::from(sorted), pair);
+    }
+
+    #[test]
+    fn ord_compares_min_first() {
+        assert!(SortedPair::new(9, 1) < SortedPair::new(2, 3));
+    }
+}
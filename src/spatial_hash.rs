@@ -0,0 +1,383 @@
+//! Finding all pairs of points within a radius, see [`SpatialHashGrid`].
+
+use crate::UnorderedPair;
+use std::collections::hash_map::{self, HashMap, RandomState};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::vec::Vec;
+
+/// A scalar type that can be used for the coordinates of the points of a [`SpatialHashGrid`].
+///
+/// Squared distances between integer points are computed in `u128`, so they don't overflow
+/// for any coordinates.
+pub trait Coordinate: Copy + Default + PartialOrd {
+    /// The type of squared distances.
+    type Squared: PartialOrd;
+
+    /// Returns the index of the cell of size `cell_size` that contains `self`,
+    /// i.e. `self / cell_size` rounded towards negative infinity.
+    fn cell(self, cell_size: Self) -> i64;
+
+    /// Returns the squared Euclidean distance between `a` and `b`.
+    fn distance_squared<const D: usize>(a: &[Self; D], b: &[Self; D]) -> Self::Squared;
+}
+
+macro_rules! impl_coordinate_float {
+    ($($t:ty),*) => {$(
+        impl Coordinate for $t {
+            type Squared = $t;
+
+            fn cell(self, cell_size: $t) -> i64 {
+                (self / cell_size).floor() as i64
+            }
+
+            fn distance_squared<const D: usize>(a: &[$t; D], b: &[$t; D]) -> $t {
+                a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum()
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_coordinate_int {
+    ($($t:ty),*) => {$(
+        impl Coordinate for $t {
+            type Squared = u128;
+
+            fn cell(self, cell_size: $t) -> i64 {
+                i64::from(self.div_euclid(cell_size))
+            }
+
+            fn distance_squared<const D: usize>(a: &[$t; D], b: &[$t; D]) -> u128 {
+                a.iter().zip(b).fold(0, |sum: u128, (a, b)| {
+                    let delta = u128::from(a.abs_diff(*b));
+                    sum.saturating_add(delta * delta)
+                })
+            }
+        }
+    )*};
+}
+
+impl_coordinate_float!(f32, f64);
+impl_coordinate_int!(i8, i16, i32, i64);
+
+/// A uniform grid that bins `D`-dimensional points by cell to find every pair of points within a radius
+///
+/// The cells are as large as the radius, so the points close to a given point
+/// are all in its cell or in one of the directly adjacent cells.
+/// [`close_pairs`](SpatialHashGrid::close_pairs) compares the points of each cell with each other
+/// and with half of the adjacent cells, so every close pair is reported exactly once,
+/// even if the points are in different cells.
+///
+/// Only the occupied cells are stored, so the points may be spread over an unbounded space.
+/// Moving a point with [`insert`](SpatialHashGrid::insert) only touches its old and new cell.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{SpatialHashGrid, UnorderedPair};
+///
+/// let mut agents = SpatialHashGrid::new(1.0);
+/// agents.insert("a", [0.0, 0.0]);
+/// agents.insert("b", [0.5, 0.5]);
+/// agents.insert("c", [3.0, 0.0]);
+/// assert_eq!(agents.close_pairs(), [UnorderedPair("a", "b")]);
+///
+/// agents.insert("c", [1.2, 0.5]);
+/// let mut pairs = agents.close_pairs();
+/// pairs.sort();
+/// assert_eq!(pairs, [UnorderedPair("a", "b"), UnorderedPair("b", "c")]);
+/// ```
+#[derive(Clone)]
+pub struct SpatialHashGrid<Id, C, const D: usize, S = RandomState> {
+    radius: C,
+    points: HashMap<Id, ([C; D], [i64; D]), S>,
+    cells: HashMap<[i64; D], Vec<Id>, S>,
+}
+
+impl<Id, C: Coordinate, const D: usize> SpatialHashGrid<Id, C, D, RandomState> {
+    /// Creates an empty `SpatialHashGrid` that finds the pairs of points at most `radius` apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` isn't positive.
+    pub fn new(radius: C) -> SpatialHashGrid<Id, C, D, RandomState> {
+        SpatialHashGrid::with_hasher(radius, RandomState::new())
+    }
+}
+
+impl<Id, C: Coordinate, const D: usize, S: Clone> SpatialHashGrid<Id, C, D, S> {
+    /// Creates an empty `SpatialHashGrid` that finds the pairs of points at most `radius` apart
+    /// and will use the given hash builder to hash IDs and cells.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radius` isn't positive.
+    pub fn with_hasher(radius: C, hash_builder: S) -> SpatialHashGrid<Id, C, D, S> {
+        assert!(radius > C::default(), "radius must be positive");
+        SpatialHashGrid {
+            radius,
+            points: HashMap::with_hasher(hash_builder.clone()),
+            cells: HashMap::with_hasher(hash_builder),
+        }
+    }
+}
+
+impl<Id, C: Coordinate, const D: usize, S> SpatialHashGrid<Id, C, D, S> {
+    /// Returns the radius within which points are considered close.
+    pub fn radius(&self) -> C {
+        self.radius
+    }
+
+    /// Returns the number of points in the grid.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns `true` if the grid contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Removes all points from the grid.
+    pub fn clear(&mut self) {
+        self.points.clear();
+        self.cells.clear();
+    }
+}
+
+impl<Id, C, const D: usize, S> SpatialHashGrid<Id, C, D, S>
+where
+    Id: Hash + Eq + Clone,
+    C: Coordinate,
+    S: BuildHasher,
+{
+    /// Inserts the point of `id`, or moves it if `id` is already present,
+    /// returning the previous position.
+    pub fn insert(&mut self, id: Id, point: [C; D]) -> Option<[C; D]> {
+        let cell = point.map(|coordinate| coordinate.cell(self.radius));
+
+        match self.points.entry(id) {
+            hash_map::Entry::Occupied(mut entry) => {
+                let (previous, previous_cell) = *entry.get();
+                if previous_cell != cell {
+                    remove_from_cell(&mut self.cells, &previous_cell, entry.key());
+                    self.cells
+                        .entry(cell)
+                        .or_default()
+                        .push(entry.key().clone());
+                }
+                entry.insert((point, cell));
+                Some(previous)
+            }
+            hash_map::Entry::Vacant(entry) => {
+                self.cells
+                    .entry(cell)
+                    .or_default()
+                    .push(entry.key().clone());
+                entry.insert((point, cell));
+                None
+            }
+        }
+    }
+
+    /// Returns the position of `id`.
+    pub fn get(&self, id: &Id) -> Option<[C; D]> {
+        self.points.get(id).map(|&(point, _)| point)
+    }
+
+    /// Removes the point of `id`, returning its position if it was present.
+    pub fn remove(&mut self, id: &Id) -> Option<[C; D]> {
+        let (point, cell) = self.points.remove(id)?;
+        remove_from_cell(&mut self.cells, &cell, id);
+        Some(point)
+    }
+
+    /// Returns every pair of points that are at most [`radius`](SpatialHashGrid::radius) apart, in arbitrary order.
+    pub fn close_pairs(&self) -> Vec<UnorderedPair<Id>> {
+        let offsets = forward_offsets::<D>();
+        let radius_squared = C::distance_squared(&[self.radius], &[C::default()]);
+        let mut pairs = Vec::new();
+
+        for (cell, ids) in &self.cells {
+            for (i, first) in ids.iter().enumerate() {
+                for second in &ids[i + 1..] {
+                    self.push_if_close(&mut pairs, &radius_squared, first, second);
+                }
+            }
+
+            for offset in &offsets {
+                // Cells at the edge of the `i64` range have no neighbors beyond it.
+                let mut neighbor = *cell;
+                let mut in_range = true;
+                for (coordinate, delta) in neighbor.iter_mut().zip(offset) {
+                    match coordinate.checked_add(*delta) {
+                        Some(sum) => *coordinate = sum,
+                        None => in_range = false,
+                    }
+                }
+                let Some(neighbor_ids) = self.cells.get(&neighbor).filter(|_| in_range) else {
+                    continue;
+                };
+                for first in ids {
+                    for second in neighbor_ids {
+                        self.push_if_close(&mut pairs, &radius_squared, first, second);
+                    }
+                }
+            }
+        }
+
+        pairs
+    }
+
+    fn push_if_close(
+        &self,
+        pairs: &mut Vec<UnorderedPair<Id>>,
+        radius_squared: &C::Squared,
+        first: &Id,
+        second: &Id,
+    ) {
+        let (a, _) = &self.points[first];
+        let (b, _) = &self.points[second];
+        if C::distance_squared(a, b) <= *radius_squared {
+            pairs.push(UnorderedPair(first.clone(), second.clone()));
+        }
+    }
+}
+
+impl<Id: fmt::Debug, C: fmt::Debug, const D: usize, S> fmt::Debug for SpatialHashGrid<Id, C, D, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpatialHashGrid")
+            .field("radius", &self.radius)
+            .field("points", &self.points)
+            .finish_non_exhaustive()
+    }
+}
+
+fn remove_from_cell<Id: Eq, const D: usize, S: BuildHasher>(
+    cells: &mut HashMap<[i64; D], Vec<Id>, S>,
+    cell: &[i64; D],
+    id: &Id,
+) {
+    if let Some(ids) = cells.get_mut(cell) {
+        if let Some(index) = ids.iter().position(|other| other == id) {
+            ids.swap_remove(index);
+        }
+        if ids.is_empty() {
+            cells.remove(cell);
+        }
+    }
+}
+
+/// Returns the offsets of the adjacent cells whose first non-zero component is positive,
+/// which contain exactly one of each pair of mutually adjacent cells.
+fn forward_offsets<const D: usize>() -> Vec<[i64; D]> {
+    let mut offsets = Vec::new();
+    let mut offset = [-1; D];
+
+    loop {
+        if offset.iter().find(|&&delta| delta != 0) == Some(&1) {
+            offsets.push(offset);
+        }
+        let Some(axis) = offset.iter().rposition(|&delta| delta < 1) else {
+            return offsets;
+        };
+        offset[axis] += 1;
+        offset[axis + 1..].fill(-1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UnorderedPairSet;
+
+    fn brute_force<const D: usize>(points: &[[f64; D]], radius: f64) -> UnorderedPairSet<usize> {
+        let mut pairs = UnorderedPairSet::new();
+        for (i, a) in points.iter().enumerate() {
+            for (j, b) in points.iter().enumerate().skip(i + 1) {
+                let distance_squared: f64 = a.iter().zip(b).map(|(a, b)| (a - b) * (a - b)).sum();
+                if distance_squared <= radius * radius {
+                    pairs.insert(i, j);
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn forward_offsets_cover_half_of_the_neighbors() {
+        assert_eq!(forward_offsets::<1>(), [[1]]);
+        assert_eq!(forward_offsets::<2>().len(), 4);
+        assert_eq!(forward_offsets::<3>().len(), 13);
+    }
+
+    #[test]
+    fn matches_brute_force_in_3d() {
+        let points: Vec<[f64; 3]> = (0..200)
+            .map(|i| {
+                let i = f64::from(i);
+                [(i * 0.37) % 5.0 - 2.5, (i * 0.71) % 4.0, (i * 1.13) % 3.0]
+            })
+            .collect();
+        let mut grid = SpatialHashGrid::new(0.6);
+        for (id, &point) in points.iter().enumerate() {
+            grid.insert(id, point);
+        }
+
+        let pairs = grid.close_pairs();
+        let found: UnorderedPairSet<_> = pairs.iter().copied().collect();
+        assert_eq!(found.len(), pairs.len());
+        assert_eq!(found, brute_force(&points, 0.6));
+    }
+
+    #[test]
+    fn moving_points_updates_cells() {
+        let mut grid = SpatialHashGrid::new(2);
+        grid.insert('a', [0, 0]);
+        grid.insert('b', [-1, -1]);
+        grid.insert('c', [10, 10]);
+        assert_eq!(grid.close_pairs(), [UnorderedPair('a', 'b')]);
+
+        assert_eq!(grid.insert('c', [-2, -1]), Some([10, 10]));
+        assert_eq!(grid.remove(&'a'), Some([0, 0]));
+        assert_eq!(grid.close_pairs(), [UnorderedPair('c', 'b')]);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.cells.len(), 1);
+    }
+
+    #[test]
+    fn cells_at_the_edge_of_the_range() {
+        let mut grid = SpatialHashGrid::new(1.0);
+        grid.insert(0, [1e300, 0.0]);
+        grid.insert(1, [-1e300, 0.0]);
+        assert!(grid.close_pairs().is_empty());
+
+        let mut grid = SpatialHashGrid::new(1);
+        grid.insert(0, [i64::MAX, i64::MIN]);
+        grid.insert(1, [i64::MAX - 1, i64::MIN]);
+        grid.insert(2, [i64::MIN, i64::MAX]);
+        assert_eq!(grid.close_pairs(), [UnorderedPair(1, 0)]);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        let mut grid =
+            SpatialHashGrid::with_hasher(1.5, BuildHasherDefault::<DefaultHasher>::default());
+        grid.insert("a", [0.0]);
+        grid.insert("b", [1.0]);
+        assert_eq!(grid.close_pairs(), [UnorderedPair("a", "b")]);
+    }
+
+    #[test]
+    fn narrow_coordinates_dont_overflow() {
+        let mut grid = SpatialHashGrid::new(20i8);
+        grid.insert('a', [100, 100]);
+        grid.insert('b', [90, 90]);
+        grid.insert('c', [-128, -128]);
+        grid.insert('d', [127, 127]);
+        assert_eq!(grid.close_pairs(), [UnorderedPair('a', 'b')]);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::parse::{parse_components, ParsePairError};
+use crate::UnorderedPair;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// A wrapper around [`UnorderedPair<T>`] that is represented as a single string such as `"3,7"`
+///
+/// With the `serde` feature, this serializes as a string instead of a sequence.
+/// This allows using pairs as keys of maps in formats that only support string keys, such as JSON.
+///
+/// The components are written smallest to largest, separated by `DELIMITER`.
+/// When parsing, the input is split at the first occurrence of `DELIMITER`,
+/// so the delimiter must not be part of the textual representation of the first component.
+///
+/// # Examples
+///
+/// ```
+/// use unordered_pair::{StringKey, UnorderedPair};
+///
+/// let key = StringKey::<_>::from(UnorderedPair(7, 3));
+/// assert_eq!(key.to_string(), "3,7");
+///
+/// let key: StringKey<u32, ';'> = "7;3".parse().unwrap();
+/// assert_eq!(key.0, UnorderedPair(3, 7));
+/// ```
+///
+/// Using pairs as JSON object keys:
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use std::collections::BTreeMap;
+/// use unordered_pair::{StringKey, UnorderedPair};
+///
+/// let mut distances = BTreeMap::<StringKey<u32>, f64>::new();
+/// distances.insert(UnorderedPair(7, 3).into(), 1.5);
+///
+/// let json = serde_json::to_string(&distances).unwrap();
+/// assert_eq!(json, r#"{"3,7":1.5}"#);
+/// assert_eq!(serde_json::from_str::<BTreeMap<StringKey<u32>, f64>>(&json).unwrap(), distances);
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct StringKey<T, const DELIMITER: char = ','>(pub UnorderedPair<T>);
+
+impl<T, const DELIMITER: char> From<UnorderedPair<T>> for StringKey<T, DELIMITER> {
+    fn from(pair: UnorderedPair<T>) -> StringKey<T, DELIMITER> {
+        StringKey(pair)
+    }
+}
+
+impl<T, const DELIMITER: char> From<StringKey<T, DELIMITER>> for UnorderedPair<T> {
+    fn from(key: StringKey<T, DELIMITER>) -> UnorderedPair<T> {
+        key.0
+    }
+}
+
+/// Computes the same hash as the underlying [`UnorderedPair<T>`]
+impl<T, const DELIMITER: char> Hash for StringKey<T, DELIMITER>
+where
+    T: Ord + Hash,
+{
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        self.0.hash(state);
+    }
+}
+
+/// Writes the components smallest to largest, separated by `DELIMITER`
+impl<T, const DELIMITER: char> fmt::Display for StringKey<T, DELIMITER>
+where
+    T: fmt::Display + Ord,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = self.0.ordered_refs();
+        write!(f, "{min}{DELIMITER}{max}")
+    }
+}
+
+impl<T, const DELIMITER: char> FromStr for StringKey<T, DELIMITER>
+where
+    T: FromStr,
+{
+    type Err = ParsePairError<T::Err>;
+
+    fn from_str(input: &str) -> Result<StringKey<T, DELIMITER>, Self::Err> {
+        parse_components(input, DELIMITER, false).map(StringKey)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const DELIMITER: char> serde::Serialize for StringKey<T, DELIMITER>
+where
+    T: fmt::Display + Ord,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const DELIMITER: char> serde::Deserialize<'de> for StringKey<T, DELIMITER>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor<T, const DELIMITER: char>(core::marker::PhantomData<T>);
+
+        impl<T, const DELIMITER: char> serde::de::Visitor<'_> for Visitor<T, DELIMITER>
+        where
+            T: FromStr,
+            T::Err: fmt::Display,
+        {
+            type Value = StringKey<T, DELIMITER>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a string of two components separated by '{DELIMITER}'")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor(core::marker::PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn display_in_canonical_order() {
+        let key: StringKey<_> = UnorderedPair(7, 3).into();
+        assert_eq!(key.to_string(), "3,7");
+        let key: StringKey<_, '-'> = UnorderedPair(3, 7).into();
+        assert_eq!(key.to_string(), "3-7");
+    }
+
+    #[test]
+    fn parse_errors_report_component() {
+        assert_eq!(
+            "3".parse::<StringKey<u8>>(),
+            Err(ParsePairError::MissingDelimiter)
+        );
+        assert!(matches!(
+            "x,7".parse::<StringKey<u8>>(),
+            Err(ParsePairError::First(_))
+        ));
+        assert!(matches!(
+            "3,300".parse::<StringKey<u8>>(),
+            Err(ParsePairError::Second(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_map_keys_round_trip() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::<StringKey<u32, ';'>, &str>::new();
+        map.insert(UnorderedPair(7, 3).into(), "a");
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"3;7":"a"}"#);
+
+        let parsed: HashMap<StringKey<u32, ';'>, &str> =
+            serde_json::from_str(r#"{"7;3":"a"}"#).unwrap();
+        assert_eq!(parsed, map);
+        assert!(
+            serde_json::from_str::<HashMap<StringKey<u32, ';'>, &str>>(r#"{"7,3":"a"}"#).is_err()
+        );
+    }
+}